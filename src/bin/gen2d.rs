@@ -73,7 +73,7 @@ fn main() {
     const REFPOOL_SIZE: usize = 2000;
     let MAX_COST: OrderedFloat<f32> = OrderedFloat::from(100.0);
     
-    let mut astar = AStar::new(REFPOOL_SIZE, MAX_COST, START, GOAL, World);
+    let mut astar: AStar<_, _, _, _> = AStar::new(REFPOOL_SIZE, MAX_COST, START, GOAL, World);
     let result = astar.compute();
 
     println!("{:?}", result);
@@ -109,7 +109,7 @@ mod test {
         use pathfinding::gen_astar::AStar;
         
         let MAX_COST: OrderedFloat<f32> = OrderedFloat::from(f32::MAX);
-        let mut astar = AStar::new(REFPOOL_SIZE, MAX_COST, START, GOAL, Adjacent);
+        let mut astar: AStar<_, _, _, _> = AStar::new(REFPOOL_SIZE, MAX_COST, START, GOAL, Adjacent);
 
         b.iter(|| {
             for _ in 0..ITERS {
@@ -279,8 +279,9 @@ mod test {
     // }
     #[bench]
     fn f32_rev(b: &mut Bencher) {
-        use pathfinding::astar_rev::AStar;
+        use pathfinding::astar::AStar;
         let mut astar: AStar<OrderedFloat<f32>, Pos> = AStar::with_refpool_size(REFPOOL_SIZE);
+        astar.set_tie_break(pathfinding::TieBreak::LowerG);
 
         b.iter(|| {
             for _ in 0..ITERS {
@@ -0,0 +1,205 @@
+#![feature(test)]
+
+use astar::pathfinding::dense::IndexedPos;
+use astar::pathfinding::{self, Goal, Movements, Pathfinder};
+use ordered_float::OrderedFloat;
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+struct Pos(i32, i32, i32);
+
+impl Goal<OrderedFloat<f32>, Pos> for Pos {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        pos == self
+    }
+    fn heuristic(&self, Pos(x1, y1, z1): &Pos) -> OrderedFloat<f32> {
+        let Pos(x0, y0, z0) = self;
+        let (x, y, z) = ((x0 - x1) as f32, (y0 - y1) as f32, (z0 - z1) as f32);
+        f32::sqrt(x * x + y * y + z * z).into()
+    }
+}
+
+/// All 26 neighbors, with a wall at x = 0 which can only be crossed
+/// through a hole around y = z = 0
+struct Adjacent;
+
+impl Movements<OrderedFloat<f32>, Pos> for Adjacent {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, OrderedFloat<f32>)> {
+        let mut neighbors = Vec::with_capacity(26);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let next = Pos(pos.0 + dx, pos.1 + dy, pos.2 + dz);
+                    if next.0 == 0 && (next.1.abs() > 2 || next.2.abs() > 2) {
+                        continue;
+                    }
+                    let cost = match dx * dx + dy * dy + dz * dz {
+                        0 => continue,
+                        1 => 1.0,
+                        2 => core::f32::consts::SQRT_2,
+                        _ => 1.732_050_8,
+                    };
+                    neighbors.push((next, cost.into()));
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+/// Integer costs, 10 per axis crossed in the same way as the float ones
+const COSTS: [i32; 4] = [0, 10, 14, 17];
+
+impl Movements<i32, Pos> for Adjacent {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        Movements::<OrderedFloat<f32>, Pos>::get_neighbors(self, pos)
+            .into_iter()
+            .map(|(next, _)| {
+                let axes = (next.0 != pos.0) as usize + (next.1 != pos.1) as usize + (next.2 != pos.2) as usize;
+                (next, COSTS[axes])
+            })
+            .collect()
+    }
+}
+
+/// Every move costs at least 10 and gets at most one closer on each axis,
+/// so this is consistent, if far from exact
+struct Chebyshev(Pos);
+
+impl Goal<i32, Pos> for Chebyshev {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        *pos == self.0
+    }
+    fn heuristic(&self, Pos(x1, y1, z1): &Pos) -> i32 {
+        let Pos(x0, y0, z0) = self.0;
+        COSTS[1] * (x0 - x1).abs().max((y0 - y1).abs()).max((z0 - z1).abs())
+    }
+}
+
+impl IndexedPos for Pos {
+    type Bounds = <(i32, i32, i32) as IndexedPos>::Bounds;
+
+    fn capacity(bounds: &Self::Bounds) -> usize {
+        <(i32, i32, i32)>::capacity(bounds)
+    }
+    fn index(&self, bounds: &Self::Bounds) -> Option<usize> {
+        (self.0, self.1, self.2).index(bounds)
+    }
+    fn from_index(index: usize, bounds: &Self::Bounds) -> Self {
+        let (x, y, z) = <(i32, i32, i32)>::from_index(index, bounds);
+        Pos(x, y, z)
+    }
+}
+
+/// Covers everything the searches from START to GOAL expand
+const BOUNDS: <Pos as IndexedPos>::Bounds = ((-32, -32, -32), (32, 32, 32));
+const START: Pos = Pos(-20, 15, -10);
+const GOAL: Pos = Pos(20, -10, 15);
+const REFPOOL_SIZE: usize = 200000;
+
+fn run<O>(name: &str, open: O)
+where
+    O: pathfinding::open_list::OpenList<Pos, pathfinding::astar::AStarNode<OrderedFloat<f32>, Pos>>,
+{
+    let mut astar = pathfinding::astar::AStar::with_open_list(REFPOOL_SIZE, open);
+    let iters = 20;
+    let t = std::time::Instant::now();
+    for _ in 0..iters {
+        extern crate test;
+        test::black_box(astar.compute(START, GOAL, Adjacent));
+    }
+    println!("{}: {:?} per iter", name, t.elapsed() / iters);
+}
+
+fn run_i32<O>(name: &str, open: O)
+where
+    O: pathfinding::open_list::OpenList<Pos, pathfinding::astar::AStarNode<i32, Pos>>,
+{
+    let mut astar = pathfinding::astar::AStar::with_open_list(REFPOOL_SIZE, open);
+    let iters = 20;
+    let t = std::time::Instant::now();
+    for _ in 0..iters {
+        extern crate test;
+        test::black_box(astar.compute(START, Chebyshev(GOAL), Adjacent));
+    }
+    println!("{}: {:?} per iter", name, t.elapsed() / iters);
+}
+
+fn main() {
+    use pathfinding::open_list::{BucketOrder, BucketQueue, DaryHeap, LazyHeap, PairingHeap, RadixHeap};
+    run("lazy heap", LazyHeap::new());
+    run("4-ary heap", DaryHeap::<_, _>::new());
+    run("pairing heap", PairingHeap::new());
+
+    let mut dense = pathfinding::dense::AStar::new(BOUNDS);
+    let t = std::time::Instant::now();
+    for _ in 0..20 {
+        extern crate test;
+        test::black_box(dense.compute(START, GOAL, Adjacent));
+    }
+    println!("dense: {:?} per iter", t.elapsed() / 20);
+
+    run_i32("i32 lazy heap", LazyHeap::new());
+    run_i32("i32 4-ary heap", DaryHeap::<_, _>::new());
+    run_i32("i32 radix heap", RadixHeap::new());
+    run_i32("i32 fifo buckets", BucketQueue::new(2 * COSTS[3], BucketOrder::Fifo));
+    run_i32("i32 lifo buckets", BucketQueue::new(2 * COSTS[3], BucketOrder::Lifo));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pathfinding::astar::AStar;
+    use pathfinding::open_list::{BucketOrder, BucketQueue, DaryHeap, LazyHeap, PairingHeap, RadixHeap};
+    use test::Bencher;
+
+    extern crate test;
+
+    #[bench]
+    fn lazy_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, LazyHeap::new());
+
+        b.iter(|| test::black_box(astar.compute(START, GOAL, Adjacent)))
+    }
+    #[bench]
+    fn dary_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, DaryHeap::<_, _>::new());
+
+        b.iter(|| test::black_box(astar.compute(START, GOAL, Adjacent)))
+    }
+    #[bench]
+    fn binary_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, DaryHeap::<_, _, 2>::new());
+
+        b.iter(|| test::black_box(astar.compute(START, GOAL, Adjacent)))
+    }
+    #[bench]
+    fn pairing_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, PairingHeap::new());
+
+        b.iter(|| test::black_box(astar.compute(START, GOAL, Adjacent)))
+    }
+    #[bench]
+    fn i32_lazy_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, LazyHeap::new());
+
+        b.iter(|| test::black_box(astar.compute(START, Chebyshev(GOAL), Adjacent)))
+    }
+    #[bench]
+    fn i32_radix_heap(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, RadixHeap::new());
+
+        b.iter(|| test::black_box(astar.compute(START, Chebyshev(GOAL), Adjacent)))
+    }
+    #[bench]
+    fn i32_bucket_queue(b: &mut Bencher) {
+        let mut astar = AStar::with_open_list(REFPOOL_SIZE, BucketQueue::new(2 * COSTS[3], BucketOrder::Lifo));
+
+        b.iter(|| test::black_box(astar.try_compute(START, Chebyshev(GOAL), Adjacent).unwrap()))
+    }
+    #[bench]
+    fn dense(b: &mut Bencher) {
+        let mut astar = pathfinding::dense::AStar::new(BOUNDS);
+
+        b.iter(|| test::black_box(astar.compute(START, GOAL, Adjacent)))
+    }
+}
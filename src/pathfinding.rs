@@ -5,11 +5,28 @@ pub mod astar_fat;
 pub mod astarf32;
 pub mod astar;
 pub mod gen_astar;
-pub mod astar_rev;
 pub mod astar_slaballoc;
 pub mod astar_arena;
 pub mod wastar;
 pub mod wastar_arena;
+pub mod namoa;
+pub mod yen;
+pub mod waypoints;
+pub mod space_time;
+pub mod sipp;
+pub mod cbs;
+pub mod whca;
+pub mod hpa;
+pub mod ch;
+pub mod alt;
+pub mod flow_field;
+pub mod hybrid_astar;
+pub mod hda;
+pub mod batch;
+pub mod backward;
+pub mod potential;
+pub mod open_list;
+pub mod dense;
 
 /// Represents a node in the pathfinding algorithms
 ///
@@ -79,10 +96,48 @@ pub trait Goal<F, Pos> {
 pub trait Movements<F, Pos> {
     fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, F)>;
 }
+/// Movements which can also be walked backwards, for searches from the goal
+pub trait ReverseMovements<F, Pos> {
+    /// Positions with a move into pos, along with the cost of that move
+    fn get_predecessors(&self, pos: Pos) -> Vec<(Pos, F)>;
+}
+
+impl<F, Pos, G> Goal<F, Pos> for &G
+where
+    G: Goal<F, Pos> + ?Sized,
+{
+    #[inline]
+    fn is_reached(&self, pos: &Pos) -> bool {
+        (**self).is_reached(pos)
+    }
+    #[inline]
+    fn heuristic(&self, pos: &Pos) -> F {
+        (**self).heuristic(pos)
+    }
+}
+impl<F, Pos, M> Movements<F, Pos> for &M
+where
+    M: Movements<F, Pos> + ?Sized,
+{
+    #[inline]
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, F)> {
+        (**self).get_neighbors(pos)
+    }
+}
+impl<F, Pos, M> ReverseMovements<F, Pos> for &M
+where
+    M: ReverseMovements<F, Pos> + ?Sized,
+{
+    #[inline]
+    fn get_predecessors(&self, pos: Pos) -> Vec<(Pos, F)> {
+        (**self).get_predecessors(pos)
+    }
+}
 
 pub trait Pathfinder {
     type F;
     type Pos;
+    /// The path is in goal to start order
     fn compute(
         &mut self,
         start: Self::Pos,
@@ -112,6 +167,23 @@ pub trait PathfinderGen {
     // fn reset(&mut self);
 }
 
+/// How nodes with the same f are ordered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Deepest node first, usually the fewest expansions on open grids
+    #[default]
+    HigherG,
+    LowerG,
+    /// The same order as HigherG, as h = f - g when f ties
+    LowerH,
+    /// First pushed first
+    Fifo,
+    /// Last pushed first
+    Lifo,
+    /// Pseudo random order from a seed, the same for every run
+    Random(u64),
+}
+
 #[derive(Debug)]
 pub enum PathResult<T> {
     Partial(T),
@@ -119,3 +191,24 @@ pub enum PathResult<T> {
     Timeout(T),
     NoPath(T),
 }
+
+
+/// Sums the cheapest edge between each consecutive pair of the path,
+/// None if two of them aren't neighbors
+pub(crate) fn path_cost<F, Pos>(path: &[Pos], movements: &impl Movements<F, Pos>) -> Option<F>
+where
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    Pos: PartialEq + Clone,
+{
+    let mut total = F::default();
+    for w in path.windows(2) {
+        let cost = movements
+            .get_neighbors(w[0].clone())
+            .into_iter()
+            .filter(|(pos, _)| *pos == w[1])
+            .map(|(_, cost)| cost)
+            .min()?;
+        total = total + cost;
+    }
+    Some(total)
+}
@@ -0,0 +1,268 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::{Goal, Movements, Node, ReverseMovements};
+
+/// Positions in the order they got settled, along with their parent
+type Settled<Pos> = Vec<(Pos, Option<Pos>)>;
+
+/// Dijkstra over every reachable position
+fn dijkstra<F, Pos>(from: Pos, neighbors: impl Fn(Pos) -> Vec<(Pos, F)>) -> (HashMap<Pos, F>, Settled<Pos>)
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    let mut heap = BinaryHeap::new();
+    let mut open: HashMap<Pos, (F, Option<Pos>)> = HashMap::new();
+    let mut closed: HashMap<Pos, F> = HashMap::new();
+    let mut order = vec![];
+    open.insert(from, (F::default(), None));
+    heap.push(Node {
+        f: F::default(),
+        pos: from,
+        t: (),
+    });
+    while let Some(node) = heap.pop() {
+        let (g, parent) = match open.remove(&node.pos) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        closed.insert(node.pos, g.clone());
+        order.push((node.pos, parent));
+        for (next, cost) in neighbors(node.pos) {
+            if closed.contains_key(&next) {
+                continue;
+            }
+            let this_g = g.clone() + cost;
+            match open.entry(next) {
+                Occupied(mut entry) => {
+                    if entry.get().0 <= this_g {
+                        continue;
+                    }
+                    entry.insert((this_g.clone(), Some(node.pos)));
+                }
+                Vacant(entry) => {
+                    entry.insert((this_g.clone(), Some(node.pos)));
+                }
+            }
+            heap.push(Node {
+                f: this_g,
+                pos: next,
+                t: (),
+            });
+        }
+    }
+    (closed, order)
+}
+
+/// `a - b`, or zero if that would be negative
+#[inline]
+fn diff<F>(a: &F, b: &F) -> F
+where
+    F: core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+{
+    if a > b {
+        a.clone() - b.clone()
+    } else {
+        F::default()
+    }
+}
+
+/// Landmarks with the cost from and to every position, for ALT heuristics
+///
+/// Distances come from full Dijkstra searches, so the part of the world
+/// reachable from the landmarks has to be finite, e.g. a fixed base map
+#[derive(Clone, Debug)]
+pub struct Landmarks<F, Pos> {
+    landmarks: Vec<Pos>,
+    from: Vec<HashMap<Pos, F>>,
+    to: Vec<HashMap<Pos, F>>,
+}
+
+impl<F, Pos> Landmarks<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            landmarks: vec![],
+            from: vec![],
+            to: vec![],
+        }
+    }
+
+    /// Adds a landmark and computes its distance tables
+    pub fn add<M>(&mut self, landmark: Pos, movements: &M)
+    where
+        M: Movements<F, Pos> + ReverseMovements<F, Pos>,
+    {
+        let (from, _) = dijkstra(landmark, |pos| movements.get_neighbors(pos));
+        let (to, _) = dijkstra(landmark, |pos| movements.get_predecessors(pos));
+        self.landmarks.push(landmark);
+        self.from.push(from);
+        self.to.push(to);
+    }
+
+    /// Picks `count` landmarks, each one as far as possible from the
+    /// previous ones, starting with the farthest position from `seed`
+    pub fn farthest<M>(seed: Pos, count: usize, movements: &M) -> Self
+    where
+        M: Movements<F, Pos> + ReverseMovements<F, Pos>,
+    {
+        let mut landmarks = Self::new();
+        let (from_seed, _) = dijkstra(seed, |pos| movements.get_neighbors(pos));
+        while landmarks.len() < count {
+            let next = from_seed
+                .keys()
+                .filter(|pos| !landmarks.landmarks.contains(pos))
+                .filter_map(|pos| {
+                    let closest = match landmarks.len() {
+                        0 => from_seed.get(pos).cloned(),
+                        _ => landmarks.from.iter().filter_map(|d| d.get(pos)).min().cloned(),
+                    };
+                    Some((closest?, *pos))
+                })
+                .max_by(|a, b| a.0.cmp(&b.0));
+            match next {
+                Some((_, pos)) => landmarks.add(pos, movements),
+                None => break,
+            }
+        }
+        landmarks
+    }
+
+    /// Picks `count` landmarks with the avoid heuristic of Goldberg and Werneck
+    ///
+    /// A shortest path tree is grown from a root, every position weighs how
+    /// badly the current landmarks bound its cost from the root, and the new
+    /// landmark is the leaf of the heaviest subtree without a landmark in it.
+    /// The root is `seed` at first, then the position farthest from the landmarks
+    pub fn avoid<M>(seed: Pos, count: usize, movements: &M) -> Self
+    where
+        M: Movements<F, Pos> + ReverseMovements<F, Pos>,
+    {
+        let mut landmarks = Self::new();
+        let mut root = seed;
+        while landmarks.len() < count {
+            let (dist, order) = dijkstra(root, |pos| movements.get_neighbors(pos));
+
+            // children are settled after their parent, so walking the order
+            // backwards sums the subtrees bottom up
+            let mut size: HashMap<Pos, Option<F>> = HashMap::new();
+            let mut children: HashMap<Pos, Vec<Pos>> = HashMap::new();
+            for &(pos, parent) in order.iter().rev() {
+                let own = match landmarks.landmarks.contains(&pos) {
+                    true => None,
+                    false => Some(diff(&dist[&pos], &landmarks.lower_bound(&root, &pos))),
+                };
+                let total = own.and_then(|own| {
+                    children
+                        .get(&pos)
+                        .into_iter()
+                        .flatten()
+                        .try_fold(own, |acc, child| Some(acc + size[child].clone()?))
+                });
+                size.insert(pos, total);
+                if let Some(parent) = parent {
+                    children.entry(parent).or_default().push(pos);
+                }
+            }
+
+            let mut cur = root;
+            while let Some(next) = children
+                .get(&cur)
+                .into_iter()
+                .flatten()
+                .filter_map(|child| Some((size[child].clone()?, *child)))
+                .max_by(|a, b| a.0.cmp(&b.0))
+            {
+                cur = next.1;
+            }
+            if cur == root && landmarks.landmarks.contains(&cur) {
+                break;
+            }
+            landmarks.add(cur, movements);
+
+            root = match dist
+                .keys()
+                .filter_map(|pos| Some((landmarks.from.iter().filter_map(|d| d.get(pos)).min()?, *pos)))
+                .max_by(|a, b| a.0.cmp(b.0))
+            {
+                Some((_, pos)) => pos,
+                None => break,
+            };
+        }
+        landmarks
+    }
+
+    pub fn len(&self) -> usize {
+        self.landmarks.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.landmarks.is_empty()
+    }
+    pub fn landmarks(&self) -> &[Pos] {
+        &self.landmarks
+    }
+
+    /// Lower bound on the cost from pos to target given by the triangle
+    /// inequality, the best over every landmark
+    pub fn lower_bound(&self, pos: &Pos, target: &Pos) -> F {
+        let mut best = F::default();
+        for (from, to) in self.from.iter().zip(self.to.iter()) {
+            if let (Some(lt), Some(lp)) = (from.get(target), from.get(pos)) {
+                best = best.max(diff(lt, lp));
+            }
+            if let (Some(pl), Some(tl)) = (to.get(pos), to.get(target)) {
+                best = best.max(diff(pl, tl));
+            }
+        }
+        best
+    }
+
+    /// Wraps a goal at `target` so its heuristic also uses the landmarks
+    pub fn goal<G>(&self, goal: G, target: Pos) -> AltGoal<'_, G, F, Pos> {
+        AltGoal {
+            goal,
+            target,
+            landmarks: self,
+        }
+    }
+}
+
+impl<F, Pos> Default for Landmarks<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Goal whose heuristic is the largest of the wrapped one and the ALT bound
+pub struct AltGoal<'a, G, F, Pos> {
+    goal: G,
+    target: Pos,
+    landmarks: &'a Landmarks<F, Pos>,
+}
+
+impl<G, F, Pos> Goal<F, Pos> for AltGoal<'_, G, F, Pos>
+where
+    G: Goal<F, Pos>,
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+{
+    #[inline]
+    fn is_reached(&self, pos: &Pos) -> bool {
+        self.goal.is_reached(pos)
+    }
+    #[inline]
+    fn heuristic(&self, pos: &Pos) -> F {
+        self.goal
+            .heuristic(pos)
+            .max(self.landmarks.lower_bound(pos, &self.target))
+    }
+}
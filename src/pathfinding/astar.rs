@@ -1,12 +1,43 @@
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::marker::PhantomData;
 use std::{fmt::Debug, hash::Hash};
 
-use super::{Goal, Movements, Node, Pathfinder};
+use super::open_list::{LazyHeap, OpenList, OpenListError};
+use super::{Goal, Movements, Node, Pathfinder, TieBreak};
+
+/// Breaks f ties, the greater key is expanded first. A search only ever
+/// uses one of the variants
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Tie<F> {
+    Max(F),
+    Min(Reverse<F>),
+    Seq(u64),
+}
+
+/// Tie key of the `pushed`th node
+fn tie<F: Clone>(tie_break: TieBreak, pushed: &mut u64, g: &F, h: &F) -> Tie<F> {
+    *pushed += 1;
+    match tie_break {
+        TieBreak::HigherG => Tie::Max(g.clone()),
+        TieBreak::LowerG => Tie::Min(Reverse(g.clone())),
+        TieBreak::LowerH => Tie::Min(Reverse(h.clone())),
+        TieBreak::Fifo => Tie::Seq(u64::MAX - *pushed),
+        TieBreak::Lifo => Tie::Seq(*pushed),
+        TieBreak::Random(seed) => {
+            // splitmix64
+            let mut z = seed.wrapping_add(pushed.wrapping_mul(0x9e3779b97f4a7c15));
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            Tie::Seq(z ^ (z >> 31))
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct AStarT<F, Pos> {
     g: F,
+    tie: Tie<F>,
     parent: Option<refpool::PoolRef<NodeLeaf<Pos>>>,
 }
 
@@ -15,7 +46,7 @@ where
     F: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.g == other.g
+        self.tie == other.tie
     }
 }
 impl<F, Pos> Eq for AStarT<F, Pos> where F: PartialEq {}
@@ -24,7 +55,7 @@ where
     F: Ord,
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.g.partial_cmp(&other.g)
+        Some(self.cmp(other))
     }
 }
 impl<F, Pos> Ord for AStarT<F, Pos>
@@ -32,8 +63,7 @@ where
     F: Ord,
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        println!("got called");
-        self.g.cmp(&other.g)
+        self.tie.cmp(&other.tie)
     }
 }
 
@@ -62,41 +92,66 @@ where
     }
 }
 
-pub struct AStar<F, Pos> {
+pub type AStarNode<F, Pos> = Node<F, Pos, AStarT<F, Pos>>;
+
+/// A* over any OpenList, the open list keeps a single node per position
+pub struct AStar<F, Pos, O = LazyHeap<Pos, AStarNode<F, Pos>>> {
     closed: HashSet<Pos>,
-    heap: BinaryHeap<Node<F, Pos, AStarT<F, Pos>>>,
-    open: HashMap<Pos, (F, AStarT<F, Pos>)>,
+    open: O,
     refpool: refpool::Pool<NodeLeaf<Pos>>,
+    tie_break: TieBreak,
+    pushed: u64,
+    marker: PhantomData<F>,
 }
-impl<F, Pos> AStar<F, Pos> {
+impl<F, Pos, O> AStar<F, Pos, O>
+where
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
     pub fn dbg(&self) {
         dbg!((
             self.refpool.get_max_size() - self.refpool.get_pool_size(),
             self.open.len(),
             self.closed.len(),
-            self.heap.len(),
         ));
     }
 }
 impl<F, Pos> AStar<F, Pos>
+where
+    F: Ord,
 {
-    pub fn with_refpool_size(size: usize) -> Self
-    where
-        F: Ord,
-    {
+    pub fn with_refpool_size(size: usize) -> Self {
+        Self::with_open_list(size, LazyHeap::new())
+    }
+}
+impl<F, Pos, O> AStar<F, Pos, O> {
+    pub fn with_open_list(size: usize, open: O) -> Self {
         Self {
             closed: Default::default(),
-            heap: Default::default(),
-            open: Default::default(),
+            open,
             refpool: refpool::Pool::new(size),
+            tie_break: TieBreak::default(),
+            pushed: 0,
+            marker: PhantomData,
         }
     }
+}
+impl<F, Pos, O> AStar<F, Pos, O>
+where
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
+    /// Applies from the next compute on
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+    pub fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
     fn clear(&mut self) {
         self.closed.clear();
-        self.heap.clear();
         self.open.clear();
+        self.pushed = 0;
     }
-    fn path(&mut self, end_node: Node<F, Pos, AStarT<F, Pos>>) -> Vec<Pos>
+    fn path(&mut self, end_node: AStarNode<F, Pos>) -> Vec<Pos>
     where
         Pos: Clone,
     {
@@ -109,46 +164,82 @@ impl<F, Pos> AStar<F, Pos>
     }
 }
 
-impl<F, Pos> Pathfinder for AStar<F, Pos>
+impl<F, Pos, O> AStar<F, Pos, O>
 where
     Pos: Hash + Eq + Clone + Copy,
     F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
 {
-    type F = F;
-    type Pos = Pos;
+    /// Like compute, but fails when the open list can't take a node
+    /// instead of returning None. The path is in goal to start order
+    pub fn try_compute(
+        &mut self,
+        start: Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Result<Option<Vec<Pos>>, OpenListError> {
+        self.search(&[(start, F::default())], &goal, &movements)
+    }
 
-    fn compute(
+    /// Searches from every (start, initial cost) at once
+    ///
+    /// Returns the index of the start the path came from along with the
+    /// path, in goal to start order like `compute`
+    pub fn compute_multi(
         &mut self,
-        start: Self::Pos,
+        starts: impl IntoIterator<Item = (Pos, F)>,
         goal: impl Goal<F, Pos>,
         movements: impl Movements<F, Pos>,
-    ) -> Option<Vec<Self::Pos>> {
+    ) -> Option<(usize, Vec<Pos>)> {
+        let starts: Vec<(Pos, F)> = starts.into_iter().collect();
+        let path = self.search(&starts, &goal, &movements).ok()??;
+        // the cheapest of the duplicated starts is the one searched from
+        let index = starts
+            .iter()
+            .enumerate()
+            .filter(|(_, (pos, _))| Some(pos) == path.last())
+            .min_by(|a, b| a.1 .1.cmp(&b.1 .1))?
+            .0;
+        Some((index, path))
+    }
+
+    fn search(
+        &mut self,
+        starts: &[(Pos, F)],
+        goal: &impl Goal<F, Pos>,
+        movements: &impl Movements<F, Pos>,
+    ) -> Result<Option<Vec<Pos>>, OpenListError> {
         self.clear();
 
-        let h = goal.heuristic(&start);
-        let start_node = Node {
-            f: h.clone(),
-            pos: start,
-            t: AStarT {
-                g: F::default(),
-                parent: None,
-            },
-        };
-        self.heap.push(start_node.clone());
+        for (start, g) in starts {
+            if self.open.get(start).is_some_and(|node| node.t.g <= *g) {
+                continue;
+            }
+            let h = goal.heuristic(start);
+            let start_node = Node {
+                f: g.clone() + h.clone(),
+                pos: *start,
+                t: AStarT {
+                    g: g.clone(),
+                    tie: tie(self.tie_break, &mut self.pushed, g, &h),
+                    parent: None,
+                },
+            };
+            self.open.push(*start, start_node)?;
+        }
 
         let max_cost: F = F::default();
-        let mut best_node = start_node;
+        let mut best_node: Option<AStarNode<F, Pos>> = None;
 
-        while let Some(node) = self.heap.pop() {
+        while let Some(node) = self.open.pop() {
             // println!("{:?}", node);
             // println!("{}", self.refpool.get_pool_size());
             if goal.is_reached(&node.pos) {
                 let res = self.path(node);
                 // self.clear();
-                return Some(res);
+                return Ok(Some(res));
             }
 
-            self.open.remove(&node.pos);
             self.closed.insert(node.pos);
             // self.visited_chunks.insert(node.pos.into());
 
@@ -166,37 +257,46 @@ where
                     continue;
                 }
 
-                match self.open.entry(neighbor_pos) {
-                    Occupied(mut entry) => {
-                        if entry.get().1.g < this_g {
-                            continue;
-                        }
-                        let node = entry.get_mut();
-                        let (f, t) = node;
-                        *f = this_g.clone() + heuristic.clone();
-                        t.g = this_g;
-                        t.parent = Some(parent.clone());
-                        if heuristic < best_node.f.clone() - best_node.t.g.clone() {
-                            best_node = (neighbor_pos, (f.clone(), t.clone())).into();
-                        }
-                    }
-                    Vacant(entry) => {
-                        let node = entry.insert((
-                            this_g.clone() + heuristic.clone(),
-                            AStarT {
-                                g: this_g,
-                                parent: Some(parent.clone()),
-                            },
-                        ));
-                        let neighbor = (neighbor_pos, node.clone()).into();
-                        if heuristic < best_node.f.clone() - best_node.t.g.clone() {
-                            best_node = Node::clone(&neighbor);
-                        }
-                        self.heap.push(neighbor);
-                    }
+                if self.open.get(&neighbor_pos).is_some_and(|open| open.t.g <= this_g) {
+                    continue;
+                }
+                let neighbor = Node {
+                    f: this_g.clone() + heuristic.clone(),
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        tie: tie(self.tie_break, &mut self.pushed, &this_g, &heuristic),
+                        g: this_g,
+                        parent: Some(parent.clone()),
+                    },
                 };
+                if best_node
+                    .as_ref()
+                    .is_none_or(|best| heuristic < best.f.clone() - best.t.g.clone())
+                {
+                    best_node = Some(neighbor.clone());
+                }
+                self.open.push(neighbor_pos, neighbor)?;
             }
         }
-        None
+        Ok(None)
+    }
+}
+
+impl<F, Pos, O> Pathfinder for AStar<F, Pos, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
+    type F = F;
+    type Pos = Pos;
+
+    fn compute(
+        &mut self,
+        start: Self::Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Option<Vec<Self::Pos>> {
+        self.search(&[(start, F::default())], &goal, &movements).ok()?
     }
 }
@@ -1,7 +1,8 @@
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::HashSet;
+use std::marker::PhantomData;
 use std::{fmt::Debug, hash::Hash};
 
+use super::open_list::{LazyHeap, OpenList, OpenListError};
 use super::{Goal, Movements, Node, Pathfinder};
 
 #[derive(Clone, Debug)]
@@ -58,11 +59,13 @@ where
     }
 }
 
-pub struct AStar<F, Pos> {
+pub type AStarNode<F, Pos> = Node<F, Pos, AStarT<F, Pos>>;
+
+pub struct AStar<F, Pos, O = LazyHeap<Pos, AStarNode<F, Pos>>> {
     closed: HashSet<Pos>,
-    heap: BinaryHeap<Node<F, Pos, AStarT<F, Pos>>>,
-    open: HashMap<Pos, (F, AStarT<F, Pos>)>,
+    open: O,
     refpool: refpool::Pool<NodeLeaf<Pos>>,
+    marker: PhantomData<F>,
 }
 
 impl<F, Pos> AStar<F, Pos> {
@@ -70,19 +73,30 @@ impl<F, Pos> AStar<F, Pos> {
     where
         F: Ord,
     {
+        Self::with_open_list(size, LazyHeap::new())
+    }
+}
+
+impl<F, Pos, O> AStar<F, Pos, O> {
+    pub fn with_open_list(size: usize, open: O) -> Self {
         Self {
             closed: Default::default(),
-            heap: Default::default(),
-            open: Default::default(),
+            open,
             refpool: refpool::Pool::new(size),
+            marker: PhantomData,
         }
     }
+}
+
+impl<F, Pos, O> AStar<F, Pos, O>
+where
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
     fn clear(&mut self) {
         self.closed.clear();
-        self.heap.clear();
         self.open.clear();
     }
-    fn path(&mut self, end_node: Node<F, Pos, AStarT<F, Pos>>) -> Vec<Pos>
+    fn path(&mut self, end_node: AStarNode<F, Pos>) -> Vec<Pos>
     where
         Pos: Clone,
     {
@@ -95,20 +109,20 @@ impl<F, Pos> AStar<F, Pos> {
     }
 }
 
-impl<F, Pos> Pathfinder for AStar<F, Pos>
+impl<F, Pos, O> AStar<F, Pos, O>
 where
     Pos: Hash + Eq + Clone + Copy,
     F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
 {
-    type F = F;
-    type Pos = Pos;
-
-    fn compute(
+    /// Like compute, but fails when the open list can't take a node
+    /// instead of returning None
+    pub fn try_compute(
         &mut self,
-        start: Self::Pos,
+        start: Pos,
         goal: impl Goal<F, Pos>,
         movements: impl Movements<F, Pos>,
-    ) -> Option<Vec<Self::Pos>> {
+    ) -> Result<Option<Vec<Pos>>, OpenListError> {
         self.clear();
 
         let h = goal.heuristic(&start);
@@ -121,21 +135,20 @@ where
                 parent: None,
             },
         };
-        self.heap.push(start_node.clone());
+        self.open.push(start, start_node.clone())?;
 
         let max_cost: F = F::default();
         let mut best_node = start_node;
 
-        while let Some(node) = self.heap.pop() {
+        while let Some(node) = self.open.pop() {
             // println!("{:?}", node);
             // println!("{}", self.refpool.get_pool_size());
             if goal.is_reached(&node.pos) {
                 let res = self.path(node);
                 // self.clear();
-                return Some(res);
+                return Ok(Some(res));
             }
 
-            self.open.remove(&node.pos);
             self.closed.insert(node.pos);
             // self.visited_chunks.insert(node.pos.into());
 
@@ -153,39 +166,43 @@ where
                     continue;
                 }
 
-                match self.open.entry(neighbor_pos) {
-                    Occupied(mut entry) => {
-                        if entry.get().1.g < this_g {
-                            continue;
-                        }
-                        let node = entry.get_mut();
-                        let (f, t) = node;
-                        *f = this_g.clone() + heuristic.clone();
-                        t.g = this_g;
-                        t.h = heuristic.clone();
-                        t.parent = Some(parent.clone());
-                        if heuristic < best_node.t.h {
-                            best_node = (neighbor_pos, (f.clone(), t.clone())).into();
-                        }
-                    }
-                    Vacant(entry) => {
-                        let node = entry.insert((
-                            this_g.clone() + heuristic.clone(),
-                            AStarT {
-                                g: this_g,
-                                h: heuristic,
-                                parent: Some(parent.clone()),
-                            },
-                        ));
-                        let neighbor = (neighbor_pos, node.clone()).into();
-                        if node.1.h < best_node.t.h {
-                            best_node = Node::clone(&neighbor);
-                        }
-                        self.heap.push(neighbor);
-                    }
+                if self.open.get(&neighbor_pos).is_some_and(|open| open.t.g <= this_g) {
+                    continue;
+                }
+                let neighbor = Node {
+                    f: this_g.clone() + heuristic.clone(),
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        g: this_g,
+                        h: heuristic,
+                        parent: Some(parent.clone()),
+                    },
                 };
+                if neighbor.t.h < best_node.t.h {
+                    best_node = Node::clone(&neighbor);
+                }
+                self.open.push(neighbor_pos, neighbor)?;
             }
         }
-        None
+        Ok(None)
+    }
+}
+
+impl<F, Pos, O> Pathfinder for AStar<F, Pos, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
+    type F = F;
+    type Pos = Pos;
+
+    fn compute(
+        &mut self,
+        start: Self::Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Option<Vec<Self::Pos>> {
+        self.try_compute(start, goal, movements).ok()?
     }
 }
@@ -1,9 +1,9 @@
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::HashSet;
 use std::{fmt::Debug, hash::Hash};
 
 use ordered_float::OrderedFloat;
 
+use super::open_list::{LazyHeap, OpenList, OpenListError};
 use super::{Goal, Movements, Node, Pathfinder};
 
 #[derive(Clone, Debug)]
@@ -59,28 +59,39 @@ where
     }
 }
 
-pub struct AStar<Pos> {
+pub type AStarNode<Pos> = Node<OrderedFloat<f32>, Pos, AStarT<Pos>>;
+
+pub struct AStar<Pos, O = LazyHeap<Pos, AStarNode<Pos>>> {
     closed: HashSet<Pos>,
-    heap: BinaryHeap<Node<OrderedFloat<f32>, Pos, AStarT<Pos>>>,
-    open: HashMap<Pos, (OrderedFloat<f32>, AStarT<Pos>)>,
+    open: O,
     refpool: refpool::Pool<NodeLeaf<Pos>>,
 }
 
 impl<Pos> AStar<Pos> {
     pub fn with_refpool_size(size: usize) -> Self {
+        Self::with_open_list(size, LazyHeap::new())
+    }
+}
+
+impl<Pos, O> AStar<Pos, O> {
+    pub fn with_open_list(size: usize, open: O) -> Self {
         Self {
             closed: Default::default(),
-            heap: Default::default(),
-            open: Default::default(),
+            open,
             refpool: refpool::Pool::new(size),
         }
     }
+}
+
+impl<Pos, O> AStar<Pos, O>
+where
+    O: OpenList<Pos, AStarNode<Pos>>,
+{
     fn clear(&mut self) {
         self.closed.clear();
-        self.heap.clear();
         self.open.clear();
     }
-    fn path(&mut self, end_node: Node<OrderedFloat<f32>, Pos, AStarT<Pos>>) -> Vec<Pos>
+    fn path(&mut self, end_node: AStarNode<Pos>) -> Vec<Pos>
     where
         Pos: Clone,
     {
@@ -93,19 +104,19 @@ impl<Pos> AStar<Pos> {
     }
 }
 
-impl<Pos> Pathfinder for AStar<Pos>
+impl<Pos, O> AStar<Pos, O>
 where
     Pos: Hash + Eq + Clone + Copy,
+    O: OpenList<Pos, AStarNode<Pos>>,
 {
-    type F = OrderedFloat<f32>;
-    type Pos = Pos;
-
-    fn compute(
+    /// Like compute, but fails when the open list can't take a node
+    /// instead of returning None
+    pub fn try_compute(
         &mut self,
-        start: Self::Pos,
+        start: Pos,
         goal: impl Goal<OrderedFloat<f32>, Pos>,
         movements: impl Movements<OrderedFloat<f32>, Pos>,
-    ) -> Option<Vec<Self::Pos>> {
+    ) -> Result<Option<Vec<Pos>>, OpenListError> {
         self.clear();
 
         let h = goal.heuristic(&start);
@@ -118,21 +129,20 @@ where
                 parent: None,
             },
         };
-        self.heap.push(start_node.clone());
+        self.open.push(start, start_node.clone())?;
 
         let max_cost: OrderedFloat<f32> = 0.0.into();
         let mut best_node = start_node;
 
-        while let Some(node) = self.heap.pop() {
+        while let Some(node) = self.open.pop() {
             // println!("{:?}", node);
             // println!("{}", self.refpool.get_pool_size());
             if goal.is_reached(&node.pos) {
                 let res = self.path(node);
                 // self.clear();
-                return Some(res);
+                return Ok(Some(res));
             }
 
-            self.open.remove(&node.pos);
             self.closed.insert(node.pos);
             // self.visited_chunks.insert(node.pos.into());
 
@@ -150,39 +160,42 @@ where
                     continue;
                 }
 
-                match self.open.entry(neighbor_pos) {
-                    Occupied(mut entry) => {
-                        if entry.get().1.g < this_g {
-                            continue;
-                        }
-                        let node = entry.get_mut();
-                        let (f, t) = node;
-                        *f = this_g + heuristic;
-                        t.g = this_g;
-                        t.h = heuristic;
-                        t.parent = Some(parent.clone());
-                        if heuristic < best_node.t.h {
-                            best_node = (neighbor_pos, (*f, t.clone())).into();
-                        }
-                    }
-                    Vacant(entry) => {
-                        let node = entry.insert((
-                            this_g + heuristic,
-                            AStarT {
-                                g: this_g,
-                                h: heuristic,
-                                parent: Some(parent.clone()),
-                            },
-                        ));
-                        let neighbor = (neighbor_pos, node.clone()).into();
-                        if heuristic < best_node.t.h {
-                            best_node = Node::clone(&neighbor);
-                        }
-                        self.heap.push(neighbor);
-                    }
+                if self.open.get(&neighbor_pos).is_some_and(|open| open.t.g <= this_g) {
+                    continue;
+                }
+                let neighbor = Node {
+                    f: this_g + heuristic,
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        g: this_g,
+                        h: heuristic,
+                        parent: Some(parent.clone()),
+                    },
                 };
+                if heuristic < best_node.t.h {
+                    best_node = Node::clone(&neighbor);
+                }
+                self.open.push(neighbor_pos, neighbor)?;
             }
         }
-        None
+        Ok(None)
+    }
+}
+
+impl<Pos, O> Pathfinder for AStar<Pos, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    O: OpenList<Pos, AStarNode<Pos>>,
+{
+    type F = OrderedFloat<f32>;
+    type Pos = Pos;
+
+    fn compute(
+        &mut self,
+        start: Self::Pos,
+        goal: impl Goal<OrderedFloat<f32>, Pos>,
+        movements: impl Movements<OrderedFloat<f32>, Pos>,
+    ) -> Option<Vec<Self::Pos>> {
+        self.try_compute(start, goal, movements).ok()?
     }
 }
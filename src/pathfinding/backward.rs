@@ -0,0 +1,141 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::{Goal, Node, ReverseMovements};
+
+/// A* searching from the goals back to the start over predecessors
+///
+/// Worth it when the goal side is the constrained one, e.g. a goal in a
+/// small room and the start in open space. Paths come out in forward order
+pub struct AStar<F, Pos> {
+    heap: BinaryHeap<Node<F, Pos, ()>>,
+    /// cost to the goals and the next position toward them
+    open: HashMap<Pos, (F, Option<Pos>)>,
+    closed: HashMap<Pos, (F, Option<Pos>)>,
+}
+
+impl<F, Pos> AStar<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            open: HashMap::new(),
+            closed: HashMap::new(),
+        }
+    }
+
+    fn reset(&mut self, goals: impl IntoIterator<Item = Pos>, toward: &impl Goal<F, Pos>) {
+        self.heap.clear();
+        self.open.clear();
+        self.closed.clear();
+        for goal in goals {
+            self.open.insert(goal, (F::default(), None));
+            self.heap.push(Node {
+                f: toward.heuristic(&goal),
+                pos: goal,
+                t: (),
+            });
+        }
+    }
+
+    /// Closes the next position, None once everything reachable is closed
+    fn step(&mut self, toward: &impl Goal<F, Pos>, movements: &impl ReverseMovements<F, Pos>) -> Option<Pos> {
+        loop {
+            let node = self.heap.pop()?;
+            let (g, next) = match self.open.remove(&node.pos) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            self.closed.insert(node.pos, (g.clone(), next));
+
+            for (pred, cost) in movements.get_predecessors(node.pos) {
+                if self.closed.contains_key(&pred) {
+                    continue;
+                }
+                let this_g = g.clone() + cost;
+                match self.open.entry(pred) {
+                    Occupied(mut entry) => {
+                        if entry.get().0 <= this_g {
+                            continue;
+                        }
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                    Vacant(entry) => {
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                }
+                self.heap.push(Node {
+                    f: this_g + toward.heuristic(&pred),
+                    pos: pred,
+                    t: (),
+                });
+            }
+            return Some(node.pos);
+        }
+    }
+
+    /// Computes the cheapest path from start to any of the goals
+    ///
+    /// `toward` estimates the cost from a position back to the start, only
+    /// its heuristic is used
+    pub fn compute(
+        &mut self,
+        start: Pos,
+        goals: impl IntoIterator<Item = Pos>,
+        toward: impl Goal<F, Pos>,
+        movements: impl ReverseMovements<F, Pos>,
+    ) -> Option<(F, Vec<Pos>)> {
+        self.reset(goals, &toward);
+        while self.step(&toward, &movements)? != start {}
+
+        let cost = self.closed[&start].0.clone();
+        let mut path = vec![start];
+        let mut cur = start;
+        while let Some(next) = self.closed[&cur].1 {
+            path.push(next);
+            cur = next;
+        }
+        Some((cost, path))
+    }
+
+    /// Cost from every position to the closest goal, up to `max_cost`
+    pub fn cost_to_goal(
+        &mut self,
+        goals: impl IntoIterator<Item = Pos>,
+        movements: impl ReverseMovements<F, Pos>,
+        max_cost: Option<F>,
+    ) -> HashMap<Pos, F> {
+        struct Dijkstra;
+        impl<F: Default, Pos> Goal<F, Pos> for Dijkstra {
+            fn is_reached(&self, _: &Pos) -> bool {
+                false
+            }
+            fn heuristic(&self, _: &Pos) -> F {
+                F::default()
+            }
+        }
+
+        self.reset(goals, &Dijkstra);
+        while let Some(pos) = self.step(&Dijkstra, &movements) {
+            if max_cost.as_ref().is_some_and(|max| self.closed[&pos].0 > *max) {
+                self.closed.remove(&pos);
+                break;
+            }
+        }
+        self.closed.drain().map(|(pos, (g, _))| (pos, g)).collect()
+    }
+}
+
+impl<F, Pos> Default for AStar<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,121 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use super::{Goal, Movements, Pathfinder};
+
+/// Work handed to a worker, run on its solver
+type Job<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+/// Solves many queries against one world on several threads
+///
+/// Solvers like `astar::AStar` hold a refpool which can't leave its thread,
+/// so every worker thread builds its own solver with `new_solver` when the
+/// batch is created, and reuses it for every query it picks up in every call
+pub struct Batch<S> {
+    workers: Vec<Sender<Job<S>>>,
+    handles: Vec<JoinHandle<()>>,
+    new_solver: Arc<dyn Fn() -> S + Send + Sync>,
+}
+
+impl<S: 'static> Batch<S> {
+    /// Starts `threads` workers, which live as long as the batch
+    pub fn new<N>(threads: usize, new_solver: N) -> Self
+    where
+        N: Fn() -> S + Send + Sync + 'static,
+    {
+        let new_solver: Arc<dyn Fn() -> S + Send + Sync> = Arc::new(new_solver);
+        let (workers, handles) = (0..threads.max(1))
+            .map(|_| {
+                let (sender, receiver) = mpsc::channel::<Job<S>>();
+                let new_solver = new_solver.clone();
+                let handle = std::thread::spawn(move || {
+                    let mut solver = new_solver();
+                    for job in receiver {
+                        job(&mut solver);
+                    }
+                });
+                (sender, handle)
+            })
+            .unzip();
+        Self {
+            workers,
+            handles,
+            new_solver,
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+impl<S, F, Pos> Batch<S>
+where
+    S: Pathfinder<F = F, Pos = Pos> + 'static,
+    Pos: Clone + Send + Sync + 'static,
+{
+    /// Computes a path for every (start, goal) query, in the queries order
+    ///
+    /// The workers outlive the call, so they get the queries and the world
+    /// shared rather than borrowed. A query which panics panics here once
+    /// the others are done, and the worker it ran on goes on with a new
+    /// solver
+    pub fn compute<G, M>(&self, queries: Arc<[(Pos, G)]>, movements: Arc<M>) -> Vec<Option<Vec<Pos>>>
+    where
+        G: Goal<F, Pos> + Send + Sync + 'static,
+        M: Movements<F, Pos> + Send + Sync + 'static,
+    {
+        let next = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+        for worker in &self.workers[..self.workers.len().min(queries.len())] {
+            let (next, queries, movements) = (next.clone(), queries.clone(), movements.clone());
+            let (new_solver, sender) = (self.new_solver.clone(), sender.clone());
+            let job: Job<S> = Box::new(move |solver: &mut S| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let (start, goal) = match queries.get(i) {
+                    Some(query) => query,
+                    None => break,
+                };
+                let result = panic::catch_unwind(AssertUnwindSafe(|| solver.compute(start.clone(), goal, &*movements)));
+                if result.is_err() {
+                    // the panic may have left the solver in any state
+                    *solver = new_solver();
+                }
+                let _ = sender.send((i, result));
+            });
+            // a worker whose new_solver panicked drops the job right away,
+            // the others pick up its share of the queries
+            let _ = worker.send(job);
+        }
+        drop(sender);
+
+        // every job holds a sender, so this only ends once they're all done
+        let mut results: Vec<Option<Vec<Pos>>> = vec![None; queries.len()];
+        let (mut received, mut panicked) = (0, None);
+        for (i, result) in receiver {
+            match result {
+                Ok(path) => results[i] = path,
+                Err(payload) => panicked = Some(payload),
+            }
+            received += 1;
+        }
+        if let Some(payload) = panicked {
+            panic::resume_unwind(payload);
+        }
+        assert_eq!(received, queries.len(), "a batch worker panicked building its solver");
+        results
+    }
+}
+
+impl<S> Drop for Batch<S> {
+    fn drop(&mut self) {
+        // closing the channels stops the workers
+        self.workers.clear();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+
+use super::space_time::{self, ReservationTable, TimedPath};
+use super::{Goal, Movements, Node};
+
+#[derive(Clone, Copy, Debug)]
+enum Constraint<Pos> {
+    /// can't be at pos at time t
+    Vertex(Pos, u32),
+    /// can't move from a to b leaving at time t
+    Edge(Pos, Pos, u32),
+}
+
+#[derive(Clone, Debug)]
+struct CtNode<F, Pos> {
+    constraints: Vec<(usize, Constraint<Pos>)>,
+    paths: Vec<TimedPath<Pos>>,
+    costs: Vec<F>,
+}
+
+/// Position of an agent at time t, it stays at the end of its path
+#[inline]
+fn at<Pos: Copy>(path: &[(Pos, u32)], t: u32) -> Pos {
+    path.get(t as usize).unwrap_or(path.last().unwrap()).0
+}
+
+/// First conflict between two agents as the constraints resolving it
+fn conflict<Pos>(paths: &[TimedPath<Pos>]) -> Option<[(usize, Constraint<Pos>); 2]>
+where
+    Pos: Eq + Copy,
+{
+    let end = paths.iter().map(|p| p.len()).max().unwrap_or(0) as u32;
+    for t in 0..end {
+        for a in 0..paths.len() {
+            for b in a + 1..paths.len() {
+                let (pa, pb) = (at(&paths[a], t), at(&paths[b], t));
+                if pa == pb {
+                    return Some([(a, Constraint::Vertex(pa, t)), (b, Constraint::Vertex(pb, t))]);
+                }
+                let (na, nb) = (at(&paths[a], t + 1), at(&paths[b], t + 1));
+                if pa != na && pa == nb && na == pb {
+                    return Some([(a, Constraint::Edge(pa, na, t)), (b, Constraint::Edge(pb, nb, t))]);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Conflict-Based Search, optimal multi-agent pathfinding
+///
+/// A high level constraint tree resolves vertex and swap conflicts between
+/// agents, every agent is planned on its own by a space-time A* respecting
+/// its constraints. All agents start at time 0 and stay at their goal
+pub struct Cbs<F, Pos> {
+    low: space_time::AStar<F, Pos>,
+    table: ReservationTable<Pos>,
+    max_nodes: usize,
+}
+
+impl<F, Pos> Cbs<F, Pos> {
+    /// `horizon` bounds the length of every agent's path in steps,
+    /// `max_nodes` the number of constraint tree nodes expanded
+    pub fn new(wait_cost: F, horizon: u32, max_nodes: usize) -> Self {
+        Self {
+            low: space_time::AStar::new(wait_cost, horizon),
+            table: Default::default(),
+            max_nodes,
+        }
+    }
+}
+
+impl<F, Pos> Cbs<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    fn plan<G: Goal<F, Pos>>(
+        &mut self,
+        agent: usize,
+        (start, goal): &(Pos, G),
+        movements: &impl Movements<F, Pos>,
+        constraints: &[(usize, Constraint<Pos>)],
+    ) -> Option<(F, Vec<(Pos, u32)>)> {
+        self.table.clear();
+        for &(a, constraint) in constraints {
+            if a != agent {
+                continue;
+            }
+            match constraint {
+                Constraint::Vertex(pos, t) => self.table.reserve(pos, t),
+                Constraint::Edge(from, to, t) => self.table.reserve_edge(from, to, t),
+            }
+        }
+        self.low.compute(*start, 0, goal, movements, &self.table)
+    }
+
+    /// Computes conflict free timed paths for every (start, goal) agent
+    ///
+    /// Returns the sum of the costs along with the paths, in the agents order
+    pub fn compute<G>(
+        &mut self,
+        agents: &[(Pos, G)],
+        movements: impl Movements<F, Pos>,
+    ) -> Option<(F, Vec<TimedPath<Pos>>)>
+    where
+        G: Goal<F, Pos>,
+    {
+        let mut root = CtNode {
+            constraints: vec![],
+            paths: Vec::with_capacity(agents.len()),
+            costs: Vec::with_capacity(agents.len()),
+        };
+        for (i, agent) in agents.iter().enumerate() {
+            let (cost, path) = self.plan(i, agent, &movements, &[])?;
+            root.paths.push(path);
+            root.costs.push(cost);
+        }
+
+        let sum = |costs: &[F]| costs.iter().cloned().fold(F::default(), |a, b| a + b);
+        let mut nodes = vec![];
+        let mut heap = BinaryHeap::new();
+        heap.push(Node {
+            f: sum(&root.costs),
+            pos: (),
+            t: 0,
+        });
+        nodes.push(root);
+
+        let mut expanded = 0;
+        while let Some(node) = heap.pop() {
+            expanded += 1;
+            if expanded > self.max_nodes {
+                return None;
+            }
+
+            let parent = &nodes[node.t];
+            let resolutions = match conflict(&parent.paths) {
+                Some(resolutions) => resolutions,
+                None => return Some((node.f, nodes.swap_remove(node.t).paths)),
+            };
+            let parent = parent.clone();
+
+            for (agent, constraint) in resolutions {
+                let mut constraints = parent.constraints.clone();
+                constraints.push((agent, constraint));
+                let (cost, path) =
+                    match self.plan(agent, &agents[agent], &movements, &constraints) {
+                        Some(plan) => plan,
+                        None => continue,
+                    };
+
+                let mut child = CtNode {
+                    constraints,
+                    paths: parent.paths.clone(),
+                    costs: parent.costs.clone(),
+                };
+                child.paths[agent] = path;
+                child.costs[agent] = cost;
+                heap.push(Node {
+                    f: sum(&child.costs),
+                    pos: (),
+                    t: nodes.len(),
+                });
+                nodes.push(child);
+            }
+        }
+        None
+    }
+}
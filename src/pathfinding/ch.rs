@@ -0,0 +1,387 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::{Movements, Node};
+
+/// Nodes settled by a witness search before giving up and adding the shortcut
+const WITNESS_LIMIT: usize = 100;
+
+/// Edge to a node and its cost, shortcuts keep the node they skip
+type Edge<F> = (usize, F, Option<usize>);
+
+/// Contraction Hierarchies for static graphs
+///
+/// Nodes are contracted one by one, least important first, adding shortcuts
+/// wherever a shortest path went through them. Queries are a bidirectional
+/// Dijkstra which only goes up the hierarchy, touching a few hundred nodes
+/// even on large graphs. Any change to the graph needs a full rebuild
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "Stored<F, Pos>",
+        into = "Stored<F, Pos>",
+        bound(
+            serialize = "F: serde::Serialize + Clone, Pos: serde::Serialize + Clone",
+            deserialize = "F: serde::Deserialize<'de>, Pos: serde::Deserialize<'de> + Hash + Eq + Clone"
+        )
+    )
+)]
+pub struct ContractionHierarchy<F, Pos> {
+    positions: Vec<Pos>,
+    index: HashMap<Pos, usize>,
+    /// edges to higher ranked nodes
+    up: Vec<Vec<Edge<F>>>,
+    /// edges from higher ranked nodes, reversed
+    down: Vec<Vec<Edge<F>>>,
+}
+
+/// What gets serialized, the index is rebuilt from the positions
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Stored<F, Pos> {
+    positions: Vec<Pos>,
+    up: Vec<Vec<Edge<F>>>,
+    down: Vec<Vec<Edge<F>>>,
+}
+
+#[cfg(feature = "serde")]
+impl<F, Pos> From<Stored<F, Pos>> for ContractionHierarchy<F, Pos>
+where
+    Pos: Hash + Eq + Clone,
+{
+    fn from(stored: Stored<F, Pos>) -> Self {
+        let index = stored
+            .positions
+            .iter()
+            .enumerate()
+            .map(|(i, pos)| (pos.clone(), i))
+            .collect();
+        Self {
+            positions: stored.positions,
+            index,
+            up: stored.up,
+            down: stored.down,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F, Pos> From<ContractionHierarchy<F, Pos>> for Stored<F, Pos> {
+    fn from(ch: ContractionHierarchy<F, Pos>) -> Self {
+        Self {
+            positions: ch.positions,
+            up: ch.up,
+            down: ch.down,
+        }
+    }
+}
+
+/// The graph while it's being contracted
+struct Contraction<F> {
+    out: Vec<HashMap<usize, (F, Option<usize>)>>,
+    inc: Vec<HashMap<usize, (F, Option<usize>)>>,
+    contracted: Vec<bool>,
+    /// contracted neighbors of every node, spreads the contraction out
+    depth: Vec<i64>,
+}
+
+impl<F> Contraction<F>
+where
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Costs from `from` without going through `skip`, up to `max`
+    fn witness(&self, from: usize, skip: usize, max: &F) -> HashMap<usize, F> {
+        let mut heap = BinaryHeap::new();
+        let mut dist: HashMap<usize, F> = HashMap::new();
+        let mut settled = 0;
+        dist.insert(from, F::default());
+        heap.push(Node {
+            f: F::default(),
+            pos: from,
+            t: (),
+        });
+        while let Some(node) = heap.pop() {
+            if dist[&node.pos] < node.f {
+                continue;
+            }
+            settled += 1;
+            if settled > WITNESS_LIMIT || node.f > *max {
+                break;
+            }
+            for (&next, (cost, _)) in self.out[node.pos].iter() {
+                if next == skip || self.contracted[next] {
+                    continue;
+                }
+                let g = node.f.clone() + cost.clone();
+                match dist.entry(next) {
+                    Occupied(mut entry) => {
+                        if *entry.get() <= g {
+                            continue;
+                        }
+                        entry.insert(g.clone());
+                    }
+                    Vacant(entry) => {
+                        entry.insert(g.clone());
+                    }
+                }
+                heap.push(Node {
+                    f: g,
+                    pos: next,
+                    t: (),
+                });
+            }
+        }
+        dist
+    }
+
+    /// Shortcuts needed to contract v
+    fn shortcuts(&self, v: usize) -> Vec<(usize, usize, F)> {
+        let mut shortcuts = vec![];
+        for (&u, (cost_in, _)) in self.inc[v].iter() {
+            if self.contracted[u] {
+                continue;
+            }
+            let targets: Vec<(usize, F)> = self.out[v]
+                .iter()
+                .filter(|(&w, _)| w != u && !self.contracted[w])
+                .map(|(&w, (cost_out, _))| (w, cost_in.clone() + cost_out.clone()))
+                .collect();
+            let max = match targets.iter().map(|(_, c)| c).max() {
+                Some(max) => max.clone(),
+                None => continue,
+            };
+            let dist = self.witness(u, v, &max);
+            for (w, cost) in targets {
+                if dist.get(&w).is_none_or(|d| *d > cost) {
+                    shortcuts.push((u, w, cost));
+                }
+            }
+        }
+        shortcuts
+    }
+
+    fn priority(&self, v: usize) -> i64 {
+        let degree = self.inc[v].keys().chain(self.out[v].keys()).filter(|&&n| !self.contracted[n]).count();
+        self.shortcuts(v).len() as i64 - degree as i64 + self.depth[v]
+    }
+
+    fn add(&mut self, from: usize, to: usize, cost: F, middle: Option<usize>) {
+        match self.out[from].entry(to) {
+            Occupied(mut entry) => {
+                if entry.get().0 <= cost {
+                    return;
+                }
+                entry.insert((cost.clone(), middle));
+            }
+            Vacant(entry) => {
+                entry.insert((cost.clone(), middle));
+            }
+        }
+        self.inc[to].insert(from, (cost, middle));
+    }
+}
+
+impl<F, Pos> ContractionHierarchy<F, Pos>
+where
+    Pos: Hash + Eq + Clone,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Preprocesses the graph made of the given positions and the moves
+    /// between them, moves leaving the set are ignored
+    pub fn build(positions: impl IntoIterator<Item = Pos>, movements: impl Movements<F, Pos>) -> Self {
+        let mut index = HashMap::new();
+        let mut list = vec![];
+        for pos in positions {
+            if let Vacant(entry) = index.entry(pos.clone()) {
+                entry.insert(list.len());
+                list.push(pos);
+            }
+        }
+        let n = list.len();
+
+        let mut graph = Contraction {
+            out: vec![HashMap::new(); n],
+            inc: vec![HashMap::new(); n],
+            contracted: vec![false; n],
+            depth: vec![0; n],
+        };
+        for (from, pos) in list.iter().enumerate() {
+            for (neighbor, cost) in movements.get_neighbors(pos.clone()) {
+                if let Some(&to) = index.get(&neighbor) {
+                    if to != from {
+                        graph.add(from, to, cost, None);
+                    }
+                }
+            }
+        }
+
+        let mut heap: BinaryHeap<Node<i64, usize, ()>> = (0..n)
+            .map(|v| Node {
+                f: graph.priority(v),
+                pos: v,
+                t: (),
+            })
+            .collect();
+        let mut rank = vec![0; n];
+        let mut next_rank = 0;
+        while let Some(node) = heap.pop() {
+            let v = node.pos;
+            if graph.contracted[v] {
+                continue;
+            }
+            // lazy updates, only contract if it's still the least important
+            let priority = graph.priority(v);
+            if heap.peek().is_some_and(|next| priority > next.f) {
+                heap.push(Node {
+                    f: priority,
+                    pos: v,
+                    t: (),
+                });
+                continue;
+            }
+
+            for (u, w, cost) in graph.shortcuts(v) {
+                graph.add(u, w, cost, Some(v));
+            }
+            graph.contracted[v] = true;
+            rank[v] = next_rank;
+            next_rank += 1;
+            let neighbors: Vec<usize> = graph.inc[v].keys().chain(graph.out[v].keys()).copied().collect();
+            for n in neighbors {
+                graph.depth[n] = graph.depth[n].max(graph.depth[v] + 1);
+            }
+        }
+
+        let mut up = vec![vec![]; n];
+        let mut down = vec![vec![]; n];
+        for (from, edges) in graph.out.into_iter().enumerate() {
+            for (to, (cost, middle)) in edges {
+                if rank[from] < rank[to] {
+                    up[from].push((to, cost, middle));
+                } else {
+                    down[to].push((from, cost, middle));
+                }
+            }
+        }
+        Self {
+            positions: list,
+            index,
+            up,
+            down,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn middle(&self, from: usize, to: usize) -> Option<usize> {
+        let edge = self.up[from]
+            .iter()
+            .filter(|e| e.0 == to)
+            .chain(self.down[to].iter().filter(|e| e.0 == from))
+            .min_by(|a, b| a.1.cmp(&b.1));
+        edge.and_then(|e| e.2)
+    }
+
+    fn unpack(&self, from: usize, to: usize, middle: Option<usize>, path: &mut Vec<Pos>) {
+        match middle {
+            Some(v) => {
+                self.unpack(from, v, self.middle(from, v), path);
+                self.unpack(v, to, self.middle(v, to), path);
+            }
+            None => path.push(self.positions[to].clone()),
+        }
+    }
+
+    /// Computes the cheapest path between two positions of the graph
+    pub fn compute(&self, start: &Pos, goal: &Pos) -> Option<(F, Vec<Pos>)> {
+        let (s, t) = (*self.index.get(start)?, *self.index.get(goal)?);
+
+        // cost and (parent, middle) of each side
+        type Dist<F> = HashMap<usize, (F, Option<(usize, Option<usize>)>)>;
+        let mut dist: [Dist<F>; 2] = [HashMap::new(), HashMap::new()];
+        let mut heaps = [BinaryHeap::new(), BinaryHeap::new()];
+        for (side, v) in [s, t].into_iter().enumerate() {
+            dist[side].insert(v, (F::default(), None));
+            heaps[side].push(Node {
+                f: F::default(),
+                pos: v,
+                t: (),
+            });
+        }
+
+        let mut best: Option<(F, usize)> = None;
+        let mut side = 0;
+        loop {
+            let done = |heap: &BinaryHeap<Node<F, usize, ()>>| match (heap.peek(), &best) {
+                (None, _) => true,
+                (Some(node), Some((cost, _))) => node.f >= *cost,
+                _ => false,
+            };
+            if done(&heaps[0]) && done(&heaps[1]) {
+                break;
+            }
+            if done(&heaps[side]) {
+                side = 1 - side;
+            }
+            let node = heaps[side].pop().unwrap();
+            if dist[side][&node.pos].0 < node.f {
+                continue;
+            }
+            if let Some((other, _)) = dist[1 - side].get(&node.pos) {
+                let total = node.f.clone() + other.clone();
+                if best.as_ref().is_none_or(|(cost, _)| total < *cost) {
+                    best = Some((total, node.pos));
+                }
+            }
+            let edges = if side == 0 { &self.up[node.pos] } else { &self.down[node.pos] };
+            for (next, cost, middle) in edges {
+                let g = node.f.clone() + cost.clone();
+                match dist[side].entry(*next) {
+                    Occupied(mut entry) => {
+                        if entry.get().0 <= g {
+                            continue;
+                        }
+                        entry.insert((g.clone(), Some((node.pos, *middle))));
+                    }
+                    Vacant(entry) => {
+                        entry.insert((g.clone(), Some((node.pos, *middle))));
+                    }
+                }
+                heaps[side].push(Node {
+                    f: g,
+                    pos: *next,
+                    t: (),
+                });
+            }
+            side = 1 - side;
+        }
+
+        let (cost, meet) = best?;
+        let mut hops = vec![];
+        let mut cur = meet;
+        while let Some((parent, middle)) = dist[0][&cur].1 {
+            hops.push((parent, cur, middle));
+            cur = parent;
+        }
+        hops.reverse();
+        let mut cur = meet;
+        while let Some((parent, middle)) = dist[1][&cur].1 {
+            hops.push((cur, parent, middle));
+            cur = parent;
+        }
+
+        let mut path = vec![self.positions[s].clone()];
+        for (from, to, middle) in hops {
+            self.unpack(from, to, middle, &mut path);
+        }
+        Some((cost, path))
+    }
+}
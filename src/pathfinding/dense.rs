@@ -0,0 +1,252 @@
+use std::collections::BinaryHeap;
+
+use super::{Goal, Movements, Node, Pathfinder};
+
+/// Position which maps to an index below a capacity set by its bounds,
+/// so solver storage can be flat arrays instead of hash maps
+pub trait IndexedPos: Sized {
+    type Bounds;
+    /// Number of indices positions within bounds map to
+    fn capacity(bounds: &Self::Bounds) -> usize;
+    /// Index of the position, None if it's out of bounds
+    fn index(&self, bounds: &Self::Bounds) -> Option<usize>;
+    /// Position at index, the opposite of `index`
+    fn from_index(index: usize, bounds: &Self::Bounds) -> Self;
+}
+
+/// Bounds are the (min, max) corners, min inclusive and max exclusive
+impl IndexedPos for (i32, i32) {
+    type Bounds = ((i32, i32), (i32, i32));
+
+    fn capacity(((x0, y0), (x1, y1)): &Self::Bounds) -> usize {
+        (x1 - x0).max(0) as usize * (y1 - y0).max(0) as usize
+    }
+    #[inline]
+    fn index(&self, ((x0, y0), (x1, y1)): &Self::Bounds) -> Option<usize> {
+        if self.0 < *x0 || self.0 >= *x1 || self.1 < *y0 || self.1 >= *y1 {
+            return None;
+        }
+        Some((self.1 - y0) as usize * (x1 - x0) as usize + (self.0 - x0) as usize)
+    }
+    #[inline]
+    fn from_index(index: usize, ((x0, y0), (x1, _)): &Self::Bounds) -> Self {
+        let width = (x1 - x0) as usize;
+        (x0 + (index % width) as i32, y0 + (index / width) as i32)
+    }
+}
+
+/// Bounds are the (min, max) corners, min inclusive and max exclusive
+impl IndexedPos for (i32, i32, i32) {
+    type Bounds = ((i32, i32, i32), (i32, i32, i32));
+
+    fn capacity(((x0, y0, z0), (x1, y1, z1)): &Self::Bounds) -> usize {
+        (x1 - x0).max(0) as usize * (y1 - y0).max(0) as usize * (z1 - z0).max(0) as usize
+    }
+    #[inline]
+    fn index(&self, ((x0, y0, z0), (x1, y1, z1)): &Self::Bounds) -> Option<usize> {
+        if self.0 < *x0 || self.0 >= *x1 || self.1 < *y0 || self.1 >= *y1 || self.2 < *z0 || self.2 >= *z1 {
+            return None;
+        }
+        let (width, depth) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        Some(((self.2 - z0) as usize * depth + (self.1 - y0) as usize) * width + (self.0 - x0) as usize)
+    }
+    #[inline]
+    fn from_index(index: usize, ((x0, y0, z0), (x1, y1, _)): &Self::Bounds) -> Self {
+        let (width, depth) = ((x1 - x0) as usize, (y1 - y0) as usize);
+        (
+            x0 + (index % width) as i32,
+            y0 + (index / width % depth) as i32,
+            z0 + (index / (width * depth)) as i32,
+        )
+    }
+}
+
+/// Flat map from index to value, values are stamped with the generation
+/// they were inserted in so clearing it is O(1)
+pub struct DenseMap<V> {
+    slots: Vec<(u32, Option<V>)>,
+    generation: u32,
+}
+
+impl<V> DenseMap<V> {
+    pub fn new(capacity: usize) -> Self {
+        let mut slots = Vec::with_capacity(capacity);
+        slots.resize_with(capacity, || (0, None));
+        Self { slots, generation: 1 }
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&V> {
+        match &self.slots[index] {
+            (generation, value) if *generation == self.generation => value.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn insert(&mut self, index: usize, value: V) {
+        self.slots[index] = (self.generation, Some(value));
+    }
+
+    #[inline]
+    pub fn contains(&self, index: usize) -> bool {
+        self.slots[index].0 == self.generation
+    }
+
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        // once in 4 billion clears the stamps have to be reset for real
+        if self.generation == 0 {
+            self.slots.iter_mut().for_each(|slot| *slot = (0, None));
+            self.generation = 1;
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+/// Flat bitset, every word is stamped with the generation it was written in
+/// so clearing it is O(1)
+pub struct DenseSet {
+    words: Vec<(u32, u64)>,
+    generation: u32,
+}
+
+impl DenseSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![(0, 0); capacity.div_ceil(64)],
+            generation: 1,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, index: usize) -> bool {
+        let (generation, bits) = self.words[index / 64];
+        generation == self.generation && bits & (1 << (index % 64)) != 0
+    }
+
+    /// Returns whether index wasn't in the set yet
+    #[inline]
+    pub fn insert(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        if word.0 != self.generation {
+            *word = (self.generation, 0);
+        }
+        let mask = 1 << (index % 64);
+        let new = word.1 & mask == 0;
+        word.1 |= mask;
+        new
+    }
+
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.words.iter_mut().for_each(|word| *word = (0, 0));
+            self.generation = 1;
+        }
+    }
+}
+
+/// A* for positions within bounds, the closed set is a bitset and the best
+/// cost and parent of every position sit in a flat array, so nothing is
+/// hashed. Positions out of bounds are treated as walls
+pub struct AStar<F, Pos: IndexedPos> {
+    bounds: Pos::Bounds,
+    heap: BinaryHeap<Node<F, usize, F>>,
+    /// best cost so far and parent index of every position
+    open: DenseMap<(F, Option<usize>)>,
+    closed: DenseSet,
+}
+
+impl<F, Pos> AStar<F, Pos>
+where
+    Pos: IndexedPos,
+    F: Ord,
+{
+    /// Allocates storage for every position within bounds up front
+    pub fn new(bounds: Pos::Bounds) -> Self {
+        let capacity = Pos::capacity(&bounds);
+        Self {
+            bounds,
+            heap: BinaryHeap::new(),
+            open: DenseMap::new(capacity),
+            closed: DenseSet::new(capacity),
+        }
+    }
+
+    pub fn bounds(&self) -> &Pos::Bounds {
+        &self.bounds
+    }
+
+    fn clear(&mut self) {
+        self.heap.clear();
+        self.open.clear();
+        self.closed.clear();
+    }
+
+    fn path(&self, mut index: usize) -> Vec<Pos> {
+        let mut path = vec![Pos::from_index(index, &self.bounds)];
+        while let Some((_, Some(parent))) = self.open.get(index) {
+            path.push(Pos::from_index(*parent, &self.bounds));
+            index = *parent;
+        }
+        path
+    }
+}
+
+impl<F, Pos> Pathfinder for AStar<F, Pos>
+where
+    Pos: IndexedPos + Clone,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    type F = F;
+    type Pos = Pos;
+
+    fn compute(
+        &mut self,
+        start: Self::Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Option<Vec<Self::Pos>> {
+        self.clear();
+        let start_index = start.index(&self.bounds)?;
+        self.open.insert(start_index, (F::default(), None));
+        self.heap.push(Node {
+            f: goal.heuristic(&start),
+            pos: start_index,
+            t: F::default(),
+        });
+
+        while let Some(node) = self.heap.pop() {
+            // superseded by a cheaper entry
+            if !self.closed.insert(node.pos) {
+                continue;
+            }
+            let pos = Pos::from_index(node.pos, &self.bounds);
+            if goal.is_reached(&pos) {
+                return Some(self.path(node.pos));
+            }
+
+            for (neighbor_pos, cost) in movements.get_neighbors(pos) {
+                let neighbor = match neighbor_pos.index(&self.bounds) {
+                    Some(neighbor) if !self.closed.contains(neighbor) => neighbor,
+                    _ => continue,
+                };
+                let this_g = node.t.clone() + cost;
+                if self.open.get(neighbor).is_some_and(|(g, _)| *g <= this_g) {
+                    continue;
+                }
+                self.open.insert(neighbor, (this_g.clone(), Some(node.pos)));
+                self.heap.push(Node {
+                    f: this_g.clone() + goal.heuristic(&neighbor_pos),
+                    pos: neighbor,
+                    t: this_g,
+                });
+            }
+        }
+        None
+    }
+}
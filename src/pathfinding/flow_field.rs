@@ -0,0 +1,146 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{Movements, Node, ReverseMovements};
+
+/// Dijkstra map shared by every agent heading to the same goals
+///
+/// The integration field holds the cost from every position to the closest
+/// goal, it's grown backwards from the goals over the reverse moves. Every
+/// position also keeps its best next step, so following the field is O(1)
+/// per step no matter how many agents use it
+pub struct FlowField<F, Pos, M> {
+    movements: M,
+    goals: Vec<Pos>,
+    max_cost: Option<F>,
+    cost: HashMap<Pos, F>,
+    next: HashMap<Pos, Pos>,
+}
+
+impl<F, Pos, M> FlowField<F, Pos, M>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    M: Movements<F, Pos> + ReverseMovements<F, Pos>,
+{
+    /// Builds the field, positions costing more than `max_cost` are left out
+    pub fn new(goals: impl IntoIterator<Item = Pos>, movements: M, max_cost: Option<F>) -> Self {
+        let mut field = Self {
+            movements,
+            goals: goals.into_iter().collect(),
+            max_cost,
+            cost: HashMap::new(),
+            next: HashMap::new(),
+        };
+        let mut heap = BinaryHeap::new();
+        for goal in field.goals.clone() {
+            field.seed(&mut heap, goal, F::default(), None);
+        }
+        field.propagate(heap);
+        field
+    }
+
+    /// Gives access to the world, call `update` with the changed positions
+    pub fn movements_mut(&mut self) -> &mut M {
+        &mut self.movements
+    }
+    pub fn goals(&self) -> &[Pos] {
+        &self.goals
+    }
+
+    /// Cost from pos to the closest goal, None if it can't reach any
+    pub fn cost(&self, pos: &Pos) -> Option<&F> {
+        self.cost.get(pos)
+    }
+
+    /// Best move from pos, None on a goal or when no goal can be reached
+    #[inline]
+    pub fn next_step(&self, pos: &Pos) -> Option<Pos> {
+        self.next.get(pos).copied()
+    }
+
+    /// Follows the field from start to a goal
+    pub fn path(&self, start: Pos) -> Option<Vec<Pos>> {
+        self.cost.get(&start)?;
+        let mut path = vec![start];
+        let mut cur = start;
+        while let Some(next) = self.next_step(&cur) {
+            path.push(next);
+            cur = next;
+        }
+        Some(path)
+    }
+
+    /// Repairs the field after the moves around the given positions changed
+    ///
+    /// Every position whose next steps went through a changed position or
+    /// one of its neighbors is dropped, then the field is grown back into
+    /// them from what's still valid. Cheaper moves spread as far as needed
+    pub fn update(&mut self, changed: impl IntoIterator<Item = Pos>) {
+        let mut stack = vec![];
+        for pos in changed {
+            stack.push(pos);
+            stack.extend(self.movements.get_neighbors(pos).into_iter().map(|(n, _)| n));
+            stack.extend(self.movements.get_predecessors(pos).into_iter().map(|(n, _)| n));
+        }
+
+        let mut children: HashMap<Pos, Vec<Pos>> = HashMap::new();
+        for (pos, next) in self.next.iter() {
+            children.entry(*next).or_default().push(*pos);
+        }
+        let mut invalid = HashSet::new();
+        while let Some(pos) = stack.pop() {
+            if invalid.insert(pos) {
+                stack.extend(children.get(&pos).into_iter().flatten());
+            }
+        }
+        for pos in invalid.iter() {
+            self.cost.remove(pos);
+            self.next.remove(pos);
+        }
+
+        let mut heap = BinaryHeap::new();
+        for goal in self.goals.clone() {
+            if invalid.contains(&goal) {
+                self.seed(&mut heap, goal, F::default(), None);
+            }
+        }
+        for pos in invalid {
+            for (neighbor, cost) in self.movements.get_neighbors(pos) {
+                if let Some(g) = self.cost.get(&neighbor) {
+                    let g = g.clone() + cost;
+                    self.seed(&mut heap, pos, g, Some(neighbor));
+                }
+            }
+        }
+        self.propagate(heap);
+    }
+
+    /// Sets the cost of pos if it's an improvement
+    fn seed(&mut self, heap: &mut BinaryHeap<Node<F, Pos, ()>>, pos: Pos, g: F, next: Option<Pos>) {
+        if self.max_cost.as_ref().is_some_and(|max| g > *max) {
+            return;
+        }
+        if self.cost.get(&pos).is_some_and(|old| *old <= g) {
+            return;
+        }
+        self.cost.insert(pos, g.clone());
+        match next {
+            Some(next) => self.next.insert(pos, next),
+            None => self.next.remove(&pos),
+        };
+        heap.push(Node { f: g, pos, t: () });
+    }
+
+    fn propagate(&mut self, mut heap: BinaryHeap<Node<F, Pos, ()>>) {
+        while let Some(node) = heap.pop() {
+            if self.cost.get(&node.pos).is_none_or(|g| *g < node.f) {
+                continue;
+            }
+            for (pred, cost) in self.movements.get_predecessors(node.pos) {
+                let g = node.f.clone() + cost;
+                self.seed(&mut heap, pred, g, Some(node.pos));
+            }
+        }
+    }
+}
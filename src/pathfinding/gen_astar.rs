@@ -1,7 +1,7 @@
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::HashSet;
 use std::{fmt::Debug, hash::Hash};
 
+use super::open_list::{LazyHeap, OpenList, OpenListError};
 use super::{Goal, Movements, Node, PathResult, PathfinderGen};
 
 #[derive(Clone, Debug)]
@@ -61,19 +61,21 @@ where
     }
 }
 
-pub struct AStar<F, Pos, G, M> {
-    start: Pos,
+pub type AStarNode<F, Pos> = Node<F, Pos, AStarT<F, Pos>>;
+
+pub struct AStar<F, Pos, G, M, O = LazyHeap<Pos, AStarNode<F, Pos>>> {
+    /// every start with its initial cost
+    starts: Vec<(Pos, F)>,
     goal: G,
     movements: M,
     max_cost: F,
     best_node: Node<F, Pos, AStarT<F, Pos>>,
-    heap: BinaryHeap<Node<F, Pos, AStarT<F, Pos>>>,
-    open: HashMap<Pos, (F, AStarT<F, Pos>)>,
+    open: O,
     closed: HashSet<Pos>,
     refpool: refpool::Pool<NodeLeaf<Pos>>,
 }
 
-impl<F, Pos, G, M> AStar<F, Pos, G, M> {
+impl<F, Pos, G, M, O> AStar<F, Pos, G, M, O> {
     fn path(&mut self, end_node: Node<F, Pos, AStarT<F, Pos>>) -> Vec<Pos>
     where
         Pos: Clone,
@@ -89,75 +91,104 @@ impl<F, Pos, G, M> AStar<F, Pos, G, M> {
     }
 }
 
-impl<F, Pos, G, M> PathfinderGen for AStar<F, Pos, G, M>
+impl<F, Pos, G, M, O> AStar<F, Pos, G, M, O>
 where
     Pos: Hash + Eq + Clone + Copy,
     F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
     G: Goal<F, Pos>,
     M: Movements<F, Pos>,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
 {
-    type F = F;
-    type Pos = Pos;
-    type Movements = M;
-    type Goal = G;
+    /// Like `new`, with the given open list
+    pub fn with_open_list(size: usize, max_cost: F, start: Pos, goal: G, movements: M, open: O) -> Self {
+        let mut astar = Self::unseeded(size, max_cost, vec![(start, F::default())], goal, movements, open);
+        // a single start always fits
+        let _ = astar.seed();
+        astar
+    }
 
-    fn new(
+    /// Searches from every (start, initial cost) at once, the path begins
+    /// at the one it came from. Fails when the open list can't take all the
+    /// starts, panics without any
+    pub fn with_starts(
         size: usize,
-        max_cost: Self::F,
-        start: Self::Pos,
-        goal: Self::Goal,
-        movements: Self::Movements,
-    ) -> Self {
-        let mut heap = BinaryHeap::new();
-        let h = goal.heuristic(&start);
-        let start_node = Node {
-            f: h.clone(),
+        max_cost: F,
+        starts: impl IntoIterator<Item = (Pos, F)>,
+        goal: G,
+        movements: M,
+        open: O,
+    ) -> Result<Self, OpenListError> {
+        let starts: Vec<(Pos, F)> = starts.into_iter().collect();
+        assert!(!starts.is_empty(), "no start to search from");
+        let mut astar = Self::unseeded(size, max_cost, starts, goal, movements, open);
+        astar.seed()?;
+        Ok(astar)
+    }
+
+    fn unseeded(size: usize, max_cost: F, starts: Vec<(Pos, F)>, goal: G, movements: M, open: O) -> Self {
+        let (start, g) = starts[0].clone();
+        let best_node = Node {
+            f: g.clone() + goal.heuristic(&start),
             pos: start,
-            t: AStarT {
-                g: F::default(),
-                parent: None,
-            },
+            t: AStarT { g, parent: None },
         };
-        heap.push(start_node.clone());
         Self {
             max_cost,
             movements,
-            start,
+            starts,
             goal,
-            heap,
-            best_node: start_node,
+            open,
+            best_node,
             closed: Default::default(),
-            open: Default::default(),
             refpool: refpool::Pool::new(size),
         }
     }
 
-    #[inline]
-    fn reset(&mut self) {
+    /// Pushes the starts, the cheapest of the duplicated ones wins
+    fn seed(&mut self) -> Result<(), OpenListError> {
+        let mut best_h = None;
+        for (start, g) in &self.starts {
+            if self.open.get(start).is_some_and(|node| node.t.g <= *g) {
+                continue;
+            }
+            let h = self.goal.heuristic(start);
+            let start_node = Node {
+                f: g.clone() + h.clone(),
+                pos: *start,
+                t: AStarT {
+                    g: g.clone(),
+                    parent: None,
+                },
+            };
+            // a cheaper duplicate replaces the best node too
+            if best_h.as_ref().is_none_or(|best| h < *best) || self.best_node.pos == *start {
+                best_h = Some(h);
+                self.best_node = start_node.clone();
+            }
+            self.open.push(*start, start_node)?;
+        }
+        Ok(())
+    }
+
+    fn restart(&mut self) {
         self.closed.clear();
-        self.heap.clear();
         self.open.clear();
-        let h = self.goal.heuristic(&self.start);
-        let start_node = Node {
-            f: h,
-            pos: self.start,
-            t: AStarT {
-                g: F::default(),
-                parent: None,
-            },
-        };
-        self.heap.push(start_node.clone());
-        self.best_node = start_node;
+        // the starts fit when the search was built, they do again
+        let _ = self.seed();
     }
 
-    fn compute(&mut self) -> PathResult<Vec<Self::Pos>> {
-        while let Some(node) = self.heap.pop() {
+    /// Runs until the goal is popped, calling `on_close` for every other
+    /// node that gets closed. Fails when the open list can't take a node
+    fn search(
+        &mut self,
+        mut on_close: impl FnMut(&AStarNode<F, Pos>),
+    ) -> Result<Option<AStarNode<F, Pos>>, OpenListError> {
+        while let Some(node) = self.open.pop() {
             if self.goal.is_reached(&node.pos) {
-                self.reset();
-                return PathResult::Complete(self.path(node));
+                return Ok(Some(node));
             }
+            on_close(&node);
 
-            self.open.remove(&node.pos);
             self.closed.insert(node.pos);
             // self.visited_chunks.insert(node.pos.into());
 
@@ -177,37 +208,112 @@ where
                     continue;
                 }
 
-                match self.open.entry(neighbor_pos) {
-                    Occupied(mut entry) => {
-                        if entry.get().1.g < this_g {
-                            continue;
-                        }
-                        let node = entry.get_mut();
-                        let (f, t) = node;
-                        *f = this_g.clone() + heuristic.clone();
-                        t.g = this_g;
-                        t.parent = Some(parent.clone());
-                        if heuristic < self.best_node.f.clone() - self.best_node.t.g.clone() {
-                            self.best_node = (neighbor_pos, (f.clone(), t.clone())).into();
-                        }
-                    }
-                    Vacant(entry) => {
-                        let node = entry.insert((
-                            this_g.clone() + heuristic.clone(),
-                            AStarT {
-                                g: this_g,
-                                parent: Some(parent.clone()),
-                            },
-                        ));
-                        let neighbor = (neighbor_pos, node.clone()).into();
-                        if heuristic < self.best_node.f.clone() - self.best_node.t.g.clone() {
-                            self.best_node = Node::clone(&neighbor);
-                        }
-                        self.heap.push(neighbor);
-                    }
+                if self.open.get(&neighbor_pos).is_some_and(|open| open.t.g <= this_g) {
+                    continue;
+                }
+                let neighbor = Node {
+                    f: this_g.clone() + heuristic.clone(),
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        g: this_g,
+                        parent: Some(parent.clone()),
+                    },
                 };
+                if heuristic < self.best_node.f.clone() - self.best_node.t.g.clone() {
+                    self.best_node = Node::clone(&neighbor);
+                }
+                self.open.push(neighbor_pos, neighbor)?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Like compute, but fails when the open list can't take a node, the
+    /// search is reset then
+    pub fn try_compute(&mut self) -> Result<PathResult<Vec<Pos>>, OpenListError> {
+        match self.search(|_| {}) {
+            Ok(Some(node)) => {
+                self.restart();
+                Ok(PathResult::Complete(self.path(node)))
+            }
+            Ok(None) => Ok(PathResult::NoPath(self.path(self.best_node.clone()))),
+            Err(err) => {
+                self.restart();
+                Err(err)
             }
         }
-        return PathResult::NoPath(self.path(self.best_node.clone()));
+    }
+
+    /// Like compute, but when the goal can't be reached the path leads to
+    /// the explored node with the lowest `metric`, the first closed among
+    /// equals
+    ///
+    /// The heuristic can be inflated or not a distance at all, the metric
+    /// only has to rank the fallbacks, e.g. the straight line distance or
+    /// being on the same level. It also gets the cost so far, so
+    /// `(distance, g)` prefers the cheapest of the closest nodes. Returns
+    /// Partial with the metric of the end, and fails when the open list
+    /// can't take a node
+    pub fn compute_closest<D: Ord>(
+        &mut self,
+        metric: impl Fn(&Pos, &F) -> D,
+    ) -> Result<PathResult<(Vec<Pos>, D)>, OpenListError> {
+        let mut closest: Option<(D, AStarNode<F, Pos>)> = None;
+        let found = self.search(|node| {
+            let d = metric(&node.pos, &node.t.g);
+            if closest.as_ref().is_none_or(|(best, _)| d < *best) {
+                closest = Some((d, node.clone()));
+            }
+        });
+        let result = match (found, closest) {
+            (Err(err), _) => Err(err),
+            (Ok(Some(node)), _) => {
+                let d = metric(&node.pos, &node.t.g);
+                Ok(PathResult::Complete((self.path(node), d)))
+            }
+            (Ok(None), Some((d, node))) => Ok(PathResult::Partial((self.path(node), d))),
+            (Ok(None), None) => {
+                let node = self.best_node.clone();
+                let d = metric(&node.pos, &node.t.g);
+                Ok(PathResult::NoPath((self.path(node), d)))
+            }
+        };
+        self.restart();
+        result
+    }
+}
+
+impl<F, Pos, G, M, O> PathfinderGen for AStar<F, Pos, G, M, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    G: Goal<F, Pos>,
+    M: Movements<F, Pos>,
+    O: OpenList<Pos, AStarNode<F, Pos>> + Default,
+{
+    type F = F;
+    type Pos = Pos;
+    type Movements = M;
+    type Goal = G;
+
+    fn new(
+        size: usize,
+        max_cost: Self::F,
+        start: Self::Pos,
+        goal: Self::Goal,
+        movements: Self::Movements,
+    ) -> Self {
+        Self::with_open_list(size, max_cost, start, goal, movements, Default::default())
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.restart();
+    }
+
+    /// Returns NoPath when the open list couldn't take a node, see
+    /// `try_compute` for the error
+    fn compute(&mut self) -> PathResult<Vec<Self::Pos>> {
+        self.try_compute().unwrap_or_else(|_| PathResult::NoPath(self.path(self.best_node.clone())))
     }
 }
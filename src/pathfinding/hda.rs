@@ -0,0 +1,227 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{Goal, Movements, Node};
+
+/// A position reached with cost g from parent
+type Message<F, Pos> = Vec<(Pos, F, Pos)>;
+
+/// State shared by the workers
+struct Shared<F, Pos> {
+    /// best goal found so far
+    incumbent: Mutex<Option<(F, Pos)>>,
+    /// bumped every time the incumbent improves, so workers only lock it
+    /// to refresh their copy of the bound when it changed
+    improvements: AtomicUsize,
+    /// idle workers and batches sent but not received yet, together so
+    /// termination is checked on a consistent snapshot
+    counters: Mutex<(usize, usize)>,
+    done: AtomicBool,
+}
+
+struct Worker<F, Pos> {
+    id: usize,
+    heap: BinaryHeap<Node<F, Pos, ()>>,
+    open: HashMap<Pos, (F, Option<Pos>)>,
+    closed: HashMap<Pos, (F, Option<Pos>)>,
+    outbox: Vec<Message<F, Pos>>,
+}
+
+#[inline]
+fn owner<Pos: Hash>(pos: &Pos, threads: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    pos.hash(&mut hasher);
+    (hasher.finish() % threads as u64) as usize
+}
+
+impl<F, Pos> Worker<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Opens pos if it's an improvement, reopening it if needed
+    fn receive(&mut self, pos: Pos, g: F, parent: Option<Pos>, goal: &impl Goal<F, Pos>) {
+        if self.closed.get(&pos).is_some_and(|(old, _)| *old <= g) {
+            return;
+        }
+        match self.open.entry(pos) {
+            Occupied(mut entry) => {
+                if entry.get().0 <= g {
+                    return;
+                }
+                entry.insert((g.clone(), parent));
+            }
+            Vacant(entry) => {
+                entry.insert((g.clone(), parent));
+            }
+        }
+        self.closed.remove(&pos);
+        self.heap.push(Node {
+            f: g + goal.heuristic(&pos),
+            pos,
+            t: (),
+        });
+    }
+
+    fn run(
+        &mut self,
+        inbox: Receiver<Message<F, Pos>>,
+        outboxes: &[Sender<Message<F, Pos>>],
+        shared: &Shared<F, Pos>,
+        goal: &impl Goal<F, Pos>,
+        movements: &impl Movements<F, Pos>,
+    ) {
+        let threads = outboxes.len();
+        let mut idle = false;
+        let (mut seen, mut bound) = (0, None);
+        loop {
+            if shared.done.load(Ordering::Acquire) {
+                return;
+            }
+            // everything received goes in before expanding, so the local
+            // open list is as close to the global one as it can be
+            let mut messages: Vec<_> = inbox.try_iter().collect();
+            if messages.is_empty() && idle {
+                match inbox.recv_timeout(Duration::from_millis(1)) {
+                    Ok(message) => messages.push(message),
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if !messages.is_empty() {
+                {
+                    let mut counters = shared.counters.lock().unwrap();
+                    counters.1 -= messages.len();
+                    if idle {
+                        counters.0 -= 1;
+                        idle = false;
+                    }
+                }
+                for (pos, g, parent) in messages.into_iter().flatten() {
+                    self.receive(pos, g, Some(parent), goal);
+                }
+            }
+
+            let improvements = shared.improvements.load(Ordering::Acquire);
+            if improvements != seen {
+                seen = improvements;
+                bound = shared.incumbent.lock().unwrap().as_ref().map(|(g, _)| g.clone());
+            }
+            let node = match self.heap.pop() {
+                Some(node) if bound.as_ref().is_none_or(|bound| node.f < *bound) => node,
+                Some(_) | None => {
+                    // nothing better than the incumbent is left here
+                    self.heap.clear();
+                    self.open.clear();
+                    let mut counters = shared.counters.lock().unwrap();
+                    counters.0 += 1;
+                    idle = true;
+                    if *counters == (threads, 0) {
+                        shared.done.store(true, Ordering::Release);
+                    }
+                    continue;
+                }
+            };
+            if !self.open.contains_key(&node.pos) {
+                continue;
+            }
+            let (g, parent) = self.open.remove(&node.pos).unwrap();
+            self.closed.insert(node.pos, (g.clone(), parent));
+
+            if goal.is_reached(&node.pos) {
+                let mut incumbent = shared.incumbent.lock().unwrap();
+                if incumbent.as_ref().is_none_or(|(best, _)| g < *best) {
+                    *incumbent = Some((g, node.pos));
+                    shared.improvements.fetch_add(1, Ordering::Release);
+                }
+                continue;
+            }
+
+            for (neighbor, cost) in movements.get_neighbors(node.pos) {
+                let this_g = g.clone() + cost;
+                match owner(&neighbor, threads) {
+                    id if id == self.id => self.receive(neighbor, this_g, Some(node.pos), goal),
+                    id => self.outbox[id].push((neighbor, this_g, node.pos)),
+                }
+            }
+            for (id, outbox) in self.outbox.iter_mut().enumerate() {
+                if outbox.is_empty() {
+                    continue;
+                }
+                // counted before it's sent, so it's never missed by the check
+                shared.counters.lock().unwrap().1 += 1;
+                let _ = outboxes[id].send(std::mem::take(outbox));
+            }
+        }
+    }
+}
+
+/// Hash Distributed A*, one search spread over several threads
+///
+/// Every position is owned by one worker picked from its hash, each worker
+/// expands its own positions and sends the successors it doesn't own to
+/// their owner. Workers don't wait on each other, nodes are reopened when
+/// a cheaper path shows up later and dropped once they can't beat the best
+/// goal found. The search ends once no worker has anything cheaper than the best goal found
+/// and no batch of nodes is still on its way
+pub struct Hda {
+    threads: usize,
+}
+
+impl Hda {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+        }
+    }
+
+    /// Computes the cheapest path from start to goal with its cost
+    pub fn compute<F, Pos, G, M>(&self, start: Pos, goal: G, movements: M) -> Option<(F, Vec<Pos>)>
+    where
+        Pos: Hash + Eq + Clone + Copy + Send + Sync,
+        F: core::ops::Add<F, Output = F> + Ord + Default + Clone + Send + Sync,
+        G: Goal<F, Pos> + Sync,
+        M: Movements<F, Pos> + Sync,
+    {
+        let shared = Shared {
+            incumbent: Mutex::new(None),
+            improvements: AtomicUsize::new(0),
+            counters: Mutex::new((0, 0)),
+            done: AtomicBool::new(false),
+        };
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..self.threads).map(|_| mpsc::channel()).unzip();
+        let mut workers: Vec<Worker<F, Pos>> = (0..self.threads)
+            .map(|id| Worker {
+                id,
+                heap: BinaryHeap::new(),
+                open: HashMap::new(),
+                closed: HashMap::new(),
+                outbox: vec![vec![]; self.threads],
+            })
+            .collect();
+        workers[owner(&start, self.threads)].receive(start, F::default(), None, &goal);
+
+        std::thread::scope(|scope| {
+            for (worker, inbox) in workers.iter_mut().zip(receivers) {
+                let (senders, shared, goal, movements) = (&senders, &shared, &goal, &movements);
+                scope.spawn(move || worker.run(inbox, senders, shared, goal, movements));
+            }
+        });
+
+        let (cost, end) = shared.incumbent.into_inner().unwrap()?;
+        let mut path = vec![end];
+        let mut cur = end;
+        while let Some(parent) = workers[owner(&cur, self.threads)].closed.get(&cur)?.1 {
+            path.push(parent);
+            cur = parent;
+        }
+        path.reverse();
+        Some((cost, path))
+    }
+}
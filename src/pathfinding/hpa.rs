@@ -0,0 +1,258 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::{Goal, Movements, Node};
+
+/// Splits the world into fixed clusters, e.g. 16x16x16 chunks
+pub trait Clustering<Pos> {
+    type Cluster: Hash + Eq + Clone;
+
+    fn cluster(&self, pos: &Pos) -> Self::Cluster;
+    /// Every position of the cluster an agent could stand on
+    fn positions(&self, cluster: &Self::Cluster) -> Vec<Pos>;
+}
+
+struct ClusterData<F, Pos> {
+    entrances: Vec<Pos>,
+    /// cheapest intra cluster cost from an abstract node to each entrance
+    edges: HashMap<Pos, Vec<(Pos, F)>>,
+}
+
+/// Hierarchical pathfinding over clusters
+///
+/// Entrances are the positions with a move leaving their cluster, the costs
+/// between the entrances of a cluster are computed once and cached. Queries
+/// run A* on that abstract graph and then refine every hop with a search
+/// restricted to its cluster. Clusters are built lazily on first use
+pub struct Hpa<F, Pos, C, M>
+where
+    C: Clustering<Pos>,
+{
+    clustering: C,
+    movements: M,
+    clusters: HashMap<C::Cluster, ClusterData<F, Pos>>,
+}
+
+impl<F, Pos, C, M> Hpa<F, Pos, C, M>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    C: Clustering<Pos>,
+    M: Movements<F, Pos>,
+{
+    pub fn new(clustering: C, movements: M) -> Self {
+        Self {
+            clustering,
+            movements,
+            clusters: Default::default(),
+        }
+    }
+    /// Gives access to the world, call `invalidate` for every changed position
+    pub fn movements_mut(&mut self) -> &mut M {
+        &mut self.movements
+    }
+    pub fn clear(&mut self) {
+        self.clusters.clear();
+    }
+
+    /// Drops the cached data of the cluster containing pos, along with
+    /// the clusters pos now has a move into, assuming moves are symmetric
+    pub fn invalidate(&mut self, pos: &Pos) {
+        self.clusters.remove(&self.clustering.cluster(pos));
+        for (neighbor, _) in self.movements.get_neighbors(*pos) {
+            self.clusters.remove(&self.clustering.cluster(&neighbor));
+        }
+    }
+
+    /// Dijkstra from `from` without leaving its cluster, stops early at `to`
+    fn local(&self, from: Pos, to: Option<&Pos>) -> HashMap<Pos, (F, Option<Pos>)> {
+        let cluster = self.clustering.cluster(&from);
+        let mut heap = BinaryHeap::new();
+        let mut open: HashMap<Pos, (F, Option<Pos>)> = HashMap::new();
+        let mut closed: HashMap<Pos, (F, Option<Pos>)> = HashMap::new();
+        open.insert(from, (F::default(), None));
+        heap.push(Node {
+            f: F::default(),
+            pos: from,
+            t: (),
+        });
+        while let Some(node) = heap.pop() {
+            let (g, parent) = match open.remove(&node.pos) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            closed.insert(node.pos, (g.clone(), parent));
+            if Some(&node.pos) == to {
+                break;
+            }
+            for (neighbor, cost) in self.movements.get_neighbors(node.pos) {
+                if closed.contains_key(&neighbor) || self.clustering.cluster(&neighbor) != cluster {
+                    continue;
+                }
+                let this_g = g.clone() + cost;
+                match open.entry(neighbor) {
+                    Occupied(mut entry) => {
+                        if entry.get().0 <= this_g {
+                            continue;
+                        }
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                    Vacant(entry) => {
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                }
+                heap.push(Node {
+                    f: this_g,
+                    pos: neighbor,
+                    t: (),
+                });
+            }
+        }
+        closed
+    }
+
+    fn build(&mut self, cluster: &C::Cluster) {
+        if self.clusters.contains_key(cluster) {
+            return;
+        }
+        let entrances: Vec<Pos> = self
+            .clustering
+            .positions(cluster)
+            .into_iter()
+            .filter(|pos| {
+                self.movements
+                    .get_neighbors(*pos)
+                    .iter()
+                    .any(|(n, _)| self.clustering.cluster(n) != *cluster)
+            })
+            .collect();
+        self.clusters.insert(
+            cluster.clone(),
+            ClusterData {
+                entrances,
+                edges: Default::default(),
+            },
+        );
+    }
+
+    /// Abstract edges inside the cluster of pos, cached
+    fn intra_edges(&mut self, pos: Pos) -> Vec<(Pos, F)> {
+        let cluster = self.clustering.cluster(&pos);
+        self.build(&cluster);
+        if let Some(edges) = self.clusters[&cluster].edges.get(&pos) {
+            return edges.clone();
+        }
+        let dist = self.local(pos, None);
+        let data = self.clusters.get_mut(&cluster).unwrap();
+        let edges: Vec<(Pos, F)> = data
+            .entrances
+            .iter()
+            .filter(|e| **e != pos)
+            .filter_map(|e| Some((*e, dist.get(e)?.0.clone())))
+            .collect();
+        data.edges.insert(pos, edges.clone());
+        edges
+    }
+
+    fn refine(&self, from: Pos, to: Pos, path: &mut Vec<Pos>) -> Option<()> {
+        if self.clustering.cluster(&from) != self.clustering.cluster(&to) {
+            path.push(to);
+            return Some(());
+        }
+        let tree = self.local(from, Some(&to));
+        let mut leg = vec![];
+        let mut cur = to;
+        while cur != from {
+            leg.push(cur);
+            cur = tree.get(&cur)?.1?;
+        }
+        path.extend(leg.into_iter().rev());
+        Some(())
+    }
+
+    /// Computes a path from start to goal, the heuristic only guides
+    /// the search over the abstract graph
+    pub fn compute(&mut self, start: Pos, goal: Pos, heuristic: impl Goal<F, Pos>) -> Option<Vec<Pos>> {
+        let goal_cluster = self.clustering.cluster(&goal);
+        let mut to_goal: HashMap<Pos, Option<F>> = HashMap::new();
+
+        let mut heap = BinaryHeap::new();
+        let mut open: HashMap<Pos, (F, Option<Pos>)> = HashMap::new();
+        let mut closed: HashMap<Pos, Option<Pos>> = HashMap::new();
+        open.insert(start, (F::default(), None));
+        heap.push(Node {
+            f: heuristic.heuristic(&start),
+            pos: start,
+            t: (),
+        });
+
+        while let Some(node) = heap.pop() {
+            let (g, parent) = match open.remove(&node.pos) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            closed.insert(node.pos, parent);
+            if node.pos == goal {
+                break;
+            }
+
+            let mut successors = self.intra_edges(node.pos);
+            let cluster = self.clustering.cluster(&node.pos);
+            successors.extend(
+                self.movements
+                    .get_neighbors(node.pos)
+                    .into_iter()
+                    .filter(|(n, _)| self.clustering.cluster(n) != cluster),
+            );
+            if cluster == goal_cluster {
+                let cost = match to_goal.get(&node.pos) {
+                    Some(cost) => cost.clone(),
+                    None => {
+                        let cost = self.local(node.pos, Some(&goal)).remove(&goal).map(|(c, _)| c);
+                        to_goal.insert(node.pos, cost.clone());
+                        cost
+                    }
+                };
+                successors.extend(cost.map(|c| (goal, c)));
+            }
+
+            for (neighbor, cost) in successors {
+                if closed.contains_key(&neighbor) {
+                    continue;
+                }
+                let this_g = g.clone() + cost;
+                match open.entry(neighbor) {
+                    Occupied(mut entry) => {
+                        if entry.get().0 <= this_g {
+                            continue;
+                        }
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                    Vacant(entry) => {
+                        entry.insert((this_g.clone(), Some(node.pos)));
+                    }
+                }
+                heap.push(Node {
+                    f: this_g + heuristic.heuristic(&neighbor),
+                    pos: neighbor,
+                    t: (),
+                });
+            }
+        }
+
+        let mut hops = vec![goal];
+        let mut cur = goal;
+        while let Some(parent) = *closed.get(&cur)? {
+            hops.push(parent);
+            cur = parent;
+        }
+        hops.reverse();
+
+        let mut path = vec![start];
+        for w in hops.windows(2) {
+            self.refine(w[0], w[1], &mut path)?;
+        }
+        Some(path)
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::f64::consts::TAU;
+
+use ordered_float::OrderedFloat;
+
+use super::{Goal, Node};
+
+pub type Cost = OrderedFloat<f64>;
+
+/// Discretized (x, y, heading) state
+pub type Cell = (i32, i32, i32);
+
+/// Continuous pose of a vehicle, heading in radians
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose {
+    pub fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+
+    /// Pose after driving `length` along an arc of the given curvature,
+    /// a negative length drives backwards
+    #[inline]
+    pub fn drive(&self, curvature: f64, length: f64) -> Self {
+        let heading = self.heading + curvature * length;
+        let (x, y) = if curvature.abs() < 1e-9 {
+            (
+                self.x + length * self.heading.cos(),
+                self.y + length * self.heading.sin(),
+            )
+        } else {
+            (
+                self.x + (heading.sin() - self.heading.sin()) / curvature,
+                self.y + (self.heading.cos() - heading.cos()) / curvature,
+            )
+        };
+        Self {
+            x,
+            y,
+            heading: mod2pi(heading),
+        }
+    }
+
+    pub fn distance(&self, other: &Pose) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+#[inline]
+fn mod2pi(angle: f64) -> f64 {
+    angle.rem_euclid(TAU)
+}
+
+/// Tells which poses the vehicle can occupy
+pub trait Footprint {
+    fn is_free(&self, pose: &Pose) -> bool;
+}
+
+impl<T: Fn(&Pose) -> bool> Footprint for T {
+    #[inline]
+    fn is_free(&self, pose: &Pose) -> bool {
+        self(pose)
+    }
+}
+
+/// Reached within a distance and heading tolerance of a pose, the heuristic
+/// is the straight line distance
+#[derive(Clone, Copy, Debug)]
+pub struct PoseGoal {
+    pub pose: Pose,
+    pub tolerance: f64,
+    pub heading_tolerance: f64,
+}
+
+impl Goal<Cost, Pose> for PoseGoal {
+    fn is_reached(&self, pos: &Pose) -> bool {
+        let turn = mod2pi(pos.heading - self.pose.heading);
+        pos.distance(&self.pose) <= self.tolerance && turn.min(TAU - turn) <= self.heading_tolerance
+    }
+    fn heuristic(&self, pos: &Pose) -> Cost {
+        pos.distance(&self.pose).into()
+    }
+}
+
+/// Shortest forward path between two poses with a bounded turning radius,
+/// as (curvature, length) segments
+pub fn dubins(from: &Pose, to: &Pose, radius: f64) -> Option<Vec<(f64, f64)>> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let d = dx.hypot(dy) / radius;
+    let theta = mod2pi(dy.atan2(dx));
+    let a = mod2pi(from.heading - theta);
+    let b = mod2pi(to.heading - theta);
+    let (sa, ca, sb, cb) = (a.sin(), a.cos(), b.sin(), b.cos());
+    let cab = (a - b).cos();
+    let (l, s, r) = (1.0, 0.0, -1.0);
+
+    let mut words: Vec<([f64; 3], [f64; 3])> = vec![];
+    // LSL
+    let p2 = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sa - sb);
+    if p2 >= 0.0 {
+        let tmp = (cb - ca).atan2(d + sa - sb);
+        words.push(([l, s, l], [mod2pi(tmp - a), p2.sqrt(), mod2pi(b - tmp)]));
+    }
+    // RSR
+    let p2 = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sb - sa);
+    if p2 >= 0.0 {
+        let tmp = (ca - cb).atan2(d - sa + sb);
+        words.push(([r, s, r], [mod2pi(a - tmp), p2.sqrt(), mod2pi(tmp - b)]));
+    }
+    // LSR
+    let p2 = -2.0 + d * d + 2.0 * cab + 2.0 * d * (sa + sb);
+    if p2 >= 0.0 {
+        let p = p2.sqrt();
+        let tmp = (-ca - cb).atan2(d + sa + sb) - (-2.0f64).atan2(p);
+        words.push(([l, s, r], [mod2pi(tmp - a), p, mod2pi(tmp - b)]));
+    }
+    // RSL
+    let p2 = -2.0 + d * d + 2.0 * cab - 2.0 * d * (sa + sb);
+    if p2 >= 0.0 {
+        let p = p2.sqrt();
+        let tmp = (ca + cb).atan2(d - sa - sb) - 2.0f64.atan2(p);
+        words.push(([r, s, l], [mod2pi(a - tmp), p, mod2pi(b - tmp)]));
+    }
+    // RLR
+    let tmp = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sa - sb)) / 8.0;
+    if tmp.abs() <= 1.0 {
+        let p = mod2pi(TAU - tmp.acos());
+        let t = mod2pi(a - (ca - cb).atan2(d - sa + sb) + p / 2.0);
+        words.push(([r, l, r], [t, p, mod2pi(a - b - t + p)]));
+    }
+    // LRL
+    let tmp = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sb - sa)) / 8.0;
+    if tmp.abs() <= 1.0 {
+        let p = mod2pi(TAU - tmp.acos());
+        let t = mod2pi(-a - (ca - cb).atan2(d + sa - sb) + p / 2.0);
+        words.push(([l, r, l], [t, p, mod2pi(b - a - t + p)]));
+    }
+
+    let (kinds, lengths) = words
+        .into_iter()
+        .min_by(|x, y| x.1.iter().sum::<f64>().total_cmp(&y.1.iter().sum()))?;
+    Some(
+        kinds
+            .iter()
+            .zip(lengths)
+            .map(|(kind, length)| (kind / radius, length * radius))
+            .collect(),
+    )
+}
+
+/// Vehicle model and discretization of the search
+#[derive(Clone, Copy, Debug)]
+pub struct Vehicle {
+    /// turning radius at full lock
+    pub min_radius: f64,
+    /// arc length of every motion primitive
+    pub step: f64,
+    /// steering angles on each side of going straight
+    pub steering: u32,
+    /// cost multiplier when driving backwards, None if the vehicle can't
+    pub reverse: Option<f64>,
+    /// extra cost per length at full lock, scaled by the steering angle
+    pub steer_penalty: f64,
+    /// extra cost of switching between forward and reverse
+    pub switch_penalty: f64,
+    pub cell_size: f64,
+    pub headings: u32,
+    /// distance between two poses checked against the footprint
+    pub resolution: f64,
+    /// expansions between two analytic shots to the goal
+    pub shot_interval: usize,
+    pub max_expansions: usize,
+}
+
+impl Vehicle {
+    pub fn new(min_radius: f64, step: f64) -> Self {
+        Self {
+            min_radius,
+            step,
+            steering: 2,
+            reverse: None,
+            steer_penalty: 0.0,
+            switch_penalty: 0.0,
+            cell_size: step / 2.0,
+            headings: 72,
+            resolution: step / 4.0,
+            shot_interval: 8,
+            max_expansions: 100_000,
+        }
+    }
+}
+
+struct HybridNode {
+    pose: Pose,
+    parent: Option<usize>,
+    /// 1 forward, -1 reverse, 0 for the start
+    direction: i8,
+    /// poses driven through since the parent, ending with pose
+    segment: Vec<Pose>,
+}
+
+/// Hybrid A* for agents with a turning radius, like cars, boats or minecarts
+///
+/// Every (x, y, heading) cell keeps the best continuous pose which reached
+/// it, successors come from driving steering arcs from that pose. Every few
+/// expansions a Dubins path to the goal is tried, which ends the search when
+/// it's collision free. With reverse allowed the shots still only go forward
+pub struct HybridAStar {
+    pub vehicle: Vehicle,
+    nodes: Vec<HybridNode>,
+    heap: BinaryHeap<Node<Cost, Cell, usize>>,
+    /// best cost to every open cell and the node holding its pose
+    open: HashMap<Cell, (Cost, usize)>,
+    closed: HashSet<Cell>,
+}
+
+impl HybridAStar {
+    pub fn new(vehicle: Vehicle) -> Self {
+        Self {
+            vehicle,
+            nodes: vec![],
+            heap: BinaryHeap::new(),
+            open: HashMap::new(),
+            closed: HashSet::new(),
+        }
+    }
+
+    fn cell(&self, pose: &Pose) -> Cell {
+        let per_heading = TAU / self.vehicle.headings as f64;
+        (
+            (pose.x / self.vehicle.cell_size).floor() as i32,
+            (pose.y / self.vehicle.cell_size).floor() as i32,
+            (mod2pi(pose.heading) / per_heading).floor() as i32 % self.vehicle.headings as i32,
+        )
+    }
+
+    /// Poses along an arc every `resolution`, None if one of them collides
+    fn sweep(&self, from: &Pose, curvature: f64, length: f64, footprint: &impl Footprint) -> Option<Vec<Pose>> {
+        let samples = (length.abs() / self.vehicle.resolution).ceil().max(1.0) as usize;
+        let mut poses = Vec::with_capacity(samples);
+        for i in 1..=samples {
+            let pose = from.drive(curvature, length * i as f64 / samples as f64);
+            if !footprint.is_free(&pose) {
+                return None;
+            }
+            poses.push(pose);
+        }
+        Some(poses)
+    }
+
+    /// Collision free Dubins path to the goal along with its length
+    fn shot(&self, from: &Pose, goal: &Pose, footprint: &impl Footprint) -> Option<(f64, Vec<Pose>)> {
+        let mut poses = vec![];
+        let mut pose = *from;
+        let mut total = 0.0;
+        for (curvature, length) in dubins(from, goal, self.vehicle.min_radius)? {
+            if length <= 0.0 {
+                continue;
+            }
+            poses.extend(self.sweep(&pose, curvature, length, footprint)?);
+            pose = *poses.last().unwrap();
+            total += length;
+        }
+        if let Some(last) = poses.last_mut() {
+            *last = *goal;
+        }
+        Some((total, poses))
+    }
+
+    fn path(&self, mut index: usize, tail: Vec<Pose>) -> Vec<Pose> {
+        let mut segments = vec![tail];
+        while let Some(parent) = self.nodes[index].parent {
+            segments.push(self.nodes[index].segment.clone());
+            index = parent;
+        }
+        let mut path = vec![self.nodes[index].pose];
+        path.extend(segments.into_iter().rev().flatten());
+        path
+    }
+
+    /// Computes a sequence of poses from start to a pose reaching `target`
+    ///
+    /// `target` guides the search and decides when a pose is close enough,
+    /// the analytic shots aim at `goal` exactly
+    pub fn compute(
+        &mut self,
+        start: Pose,
+        goal: Pose,
+        target: impl Goal<Cost, Pose>,
+        footprint: impl Footprint,
+    ) -> Option<(Cost, Vec<Pose>)> {
+        self.nodes.clear();
+        self.heap.clear();
+        self.open.clear();
+        self.closed.clear();
+
+        let vehicle = self.vehicle;
+        let max_curvature = 1.0 / vehicle.min_radius;
+        let directions: &[i8] = match vehicle.reverse {
+            Some(_) => &[1, -1],
+            None => &[1],
+        };
+
+        let cell = self.cell(&start);
+        self.nodes.push(HybridNode {
+            pose: start,
+            parent: None,
+            direction: 0,
+            segment: vec![],
+        });
+        self.open.insert(cell, (Cost::default(), 0));
+        self.heap.push(Node {
+            f: target.heuristic(&start),
+            pos: cell,
+            t: 0,
+        });
+
+        let mut expanded = 0;
+        while let Some(node) = self.heap.pop() {
+            // cells can be reopened by a better pose, skip the older ones
+            let g = match self.open.get(&node.pos) {
+                Some(&(g, index)) if index == node.t => g,
+                _ => continue,
+            };
+            self.open.remove(&node.pos);
+            self.closed.insert(node.pos);
+            let index = node.t;
+            let pose = self.nodes[index].pose;
+
+            if target.is_reached(&pose) {
+                return Some((g, self.path(index, vec![])));
+            }
+            expanded += 1;
+            if expanded > vehicle.max_expansions {
+                return None;
+            }
+            if (expanded - 1) % vehicle.shot_interval.max(1) == 0 {
+                if let Some((length, tail)) = self.shot(&pose, &goal, &footprint) {
+                    return Some((g + length, self.path(index, tail)));
+                }
+            }
+
+            let steering = vehicle.steering as i32;
+            for &direction in directions {
+                for steer in -steering..=steering {
+                    let ratio = match steering {
+                        0 => 0.0,
+                        _ => steer as f64 / steering as f64,
+                    };
+                    let length = vehicle.step * direction as f64;
+                    let segment = match self.sweep(&pose, ratio * max_curvature, length, &footprint) {
+                        Some(segment) => segment,
+                        None => continue,
+                    };
+                    let next = *segment.last().unwrap();
+                    let cell = self.cell(&next);
+                    if self.closed.contains(&cell) {
+                        continue;
+                    }
+
+                    let mut cost = vehicle.step * (1.0 + vehicle.steer_penalty * ratio.abs());
+                    if direction < 0 {
+                        cost *= vehicle.reverse.unwrap_or(1.0);
+                    }
+                    let previous = self.nodes[index].direction;
+                    if previous != 0 && previous != direction {
+                        cost += vehicle.switch_penalty;
+                    }
+                    let this_g = g + cost;
+                    let entry = (this_g, self.nodes.len());
+                    match self.open.entry(cell) {
+                        Occupied(mut occupied) => {
+                            if occupied.get().0 <= this_g {
+                                continue;
+                            }
+                            occupied.insert(entry);
+                        }
+                        Vacant(vacant) => {
+                            vacant.insert(entry);
+                        }
+                    }
+                    self.heap.push(Node {
+                        f: this_g + target.heuristic(&next),
+                        pos: cell,
+                        t: self.nodes.len(),
+                    });
+                    self.nodes.push(HybridNode {
+                        pose: next,
+                        parent: Some(index),
+                        direction,
+                        segment,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
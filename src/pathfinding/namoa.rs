@@ -0,0 +1,222 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+use super::{Goal, Movements, Node};
+
+/// A cost which can be compared by Pareto dominance
+///
+/// The Ord of the cost is only used to order the open list and has to be
+/// a linear extension of the dominance relation (lexicographic works),
+/// so a label is never popped before a label dominating it
+pub trait Dominance: Ord {
+    /// Whether every component of self is less or equal to the one in other
+    fn dominates(&self, other: &Self) -> bool;
+}
+
+/// A fixed size vector of costs, e.g. `[travel time, danger, blocks used]`
+///
+/// Addition is component wise, the Ord is lexicographic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CostVec<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> Default for CostVec<T, N>
+where
+    T: Default + Copy,
+{
+    fn default() -> Self {
+        Self([T::default(); N])
+    }
+}
+impl<T, const N: usize> Add for CostVec<T, N>
+where
+    T: Add<T, Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a = *a + b;
+        }
+        self
+    }
+}
+impl<T, const N: usize> Dominance for CostVec<T, N>
+where
+    T: Ord,
+{
+    #[inline]
+    fn dominates(&self, other: &Self) -> bool {
+        self.0.iter().zip(other.0.iter()).all(|(a, b)| a <= b)
+    }
+}
+impl<T, const N: usize> From<[T; N]> for CostVec<T, N> {
+    fn from(costs: [T; N]) -> Self {
+        Self(costs)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Open,
+    Closed,
+    Pruned,
+}
+
+#[derive(Clone, Debug)]
+struct Label<F, Pos> {
+    pos: Pos,
+    g: F,
+    parent: Option<usize>,
+    state: State,
+}
+
+/// NAMOA*, multi-objective A* returning every Pareto optimal path
+///
+/// Instead of one g per position, every position keeps the set of
+/// non-dominated labels reaching it. The heuristic has to be admissible
+/// in every component for the front to be complete
+pub struct AStar<F, Pos> {
+    labels: Vec<Label<F, Pos>>,
+    heap: BinaryHeap<Node<F, Pos, usize>>,
+    /// (open, closed) labels of each position
+    sets: HashMap<Pos, (Vec<usize>, Vec<usize>)>,
+}
+
+impl<F, Pos> Default for AStar<F, Pos> {
+    fn default() -> Self {
+        Self {
+            labels: Default::default(),
+            heap: Default::default(),
+            sets: Default::default(),
+        }
+    }
+}
+
+impl<F, Pos> AStar<F, Pos> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn clear(&mut self) {
+        self.labels.clear();
+        self.heap.clear();
+        self.sets.clear();
+    }
+    fn path(&self, mut idx: usize) -> Vec<Pos>
+    where
+        Pos: Clone,
+    {
+        let mut v = vec![self.labels[idx].pos.clone()];
+        while let Some(parent) = self.labels[idx].parent {
+            v.push(self.labels[parent].pos.clone());
+            idx = parent;
+        }
+        v.reverse();
+        v
+    }
+}
+
+impl<F, Pos> AStar<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: Add<F, Output = F> + Dominance + Default + Clone,
+{
+    /// Computes the Pareto front of paths from start to the goal
+    ///
+    /// Every returned path is loopless and not dominated by any other path,
+    /// it's up to the caller to pick a trade-off between them
+    pub fn compute(
+        &mut self,
+        start: Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Vec<(F, Vec<Pos>)> {
+        self.clear();
+
+        self.labels.push(Label {
+            pos: start,
+            g: F::default(),
+            parent: None,
+            state: State::Open,
+        });
+        self.sets.entry(start).or_default().0.push(0);
+        self.heap.push(Node {
+            f: goal.heuristic(&start),
+            pos: start,
+            t: 0,
+        });
+
+        let mut solutions: Vec<usize> = vec![];
+
+        while let Some(node) = self.heap.pop() {
+            let idx = node.t;
+            if self.labels[idx].state != State::Open {
+                continue;
+            }
+
+            let (open, closed) = self.sets.get_mut(&node.pos).unwrap();
+            open.retain(|&i| i != idx);
+            if solutions
+                .iter()
+                .any(|&s| self.labels[s].g.dominates(&node.f))
+            {
+                self.labels[idx].state = State::Pruned;
+                continue;
+            }
+            closed.push(idx);
+            self.labels[idx].state = State::Closed;
+
+            if goal.is_reached(&node.pos) {
+                solutions.push(idx);
+                continue;
+            }
+
+            let g = self.labels[idx].g.clone();
+            for (neighbor_pos, cost) in movements.get_neighbors(node.pos) {
+                let this_g = g.clone() + cost;
+
+                let (open, closed) = self.sets.entry(neighbor_pos).or_default();
+                let labels = &mut self.labels;
+                if open
+                    .iter()
+                    .chain(closed.iter())
+                    .any(|&i| labels[i].g.dominates(&this_g))
+                {
+                    continue;
+                }
+                open.retain(|&i| {
+                    let dominated = this_g.dominates(&labels[i].g);
+                    if dominated {
+                        labels[i].state = State::Pruned;
+                    }
+                    !dominated
+                });
+                closed.retain(|&i| !this_g.dominates(&labels[i].g));
+
+                let this_f = this_g.clone() + goal.heuristic(&neighbor_pos);
+                if solutions.iter().any(|&s| labels[s].g.dominates(&this_f)) {
+                    continue;
+                }
+
+                let new_idx = labels.len();
+                labels.push(Label {
+                    pos: neighbor_pos,
+                    g: this_g,
+                    parent: Some(idx),
+                    state: State::Open,
+                });
+                open.push(new_idx);
+                self.heap.push(Node {
+                    f: this_f,
+                    pos: neighbor_pos,
+                    t: new_idx,
+                });
+            }
+        }
+
+        solutions
+            .into_iter()
+            .map(|s| (self.labels[s].g.clone(), self.path(s)))
+            .collect()
+    }
+}
@@ -0,0 +1,875 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use super::Node;
+
+/// Why an open list couldn't take an item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenListError {
+    /// f is further from the others than the open list can span
+    OutOfSpan,
+}
+
+impl std::fmt::Display for OpenListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenListError::OutOfSpan => write!(f, "f out of the span of the open list"),
+        }
+    }
+}
+
+impl std::error::Error for OpenListError {}
+
+/// Open list of a search, items are kept under a key, usually their
+/// position, and the greatest one is popped first like in a BinaryHeap
+pub trait OpenList<K, T> {
+    /// Adds item under key, replacing the item already under it if any
+    fn push(&mut self, key: K, item: T) -> Result<(), OpenListError>;
+    /// Removes the greatest item
+    fn pop(&mut self) -> Option<T>;
+    /// Item under key, if it's still in the list
+    fn get(&self, key: &K) -> Option<&T>;
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Heap entry ordered by its item only
+struct Stamped<K, T> {
+    item: T,
+    key: K,
+    stamp: u64,
+}
+
+impl<K, T: PartialEq> PartialEq for Stamped<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item
+    }
+}
+impl<K, T: Eq> Eq for Stamped<K, T> {}
+impl<K, T: Ord> PartialOrd for Stamped<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<K, T: Ord> Ord for Stamped<K, T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.item.cmp(&other.item)
+    }
+}
+
+/// std BinaryHeap with lazy deletion
+///
+/// A replaced item stays in the heap and is skipped once it's popped, so
+/// pushes are cheap but the heap grows with every replacement
+pub struct LazyHeap<K, T> {
+    heap: BinaryHeap<Stamped<K, T>>,
+    /// stamp of the live entry of every key and its item
+    live: HashMap<K, (u64, T)>,
+    stamp: u64,
+}
+
+impl<K, T: Ord> LazyHeap<K, T> {
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            live: HashMap::new(),
+            stamp: 0,
+        }
+    }
+}
+
+impl<K, T: Ord> Default for LazyHeap<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T> OpenList<K, T> for LazyHeap<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Ord + Clone,
+{
+    fn push(&mut self, key: K, item: T) -> Result<(), OpenListError> {
+        self.stamp += 1;
+        self.live.insert(key.clone(), (self.stamp, item.clone()));
+        self.heap.push(Stamped {
+            item,
+            key,
+            stamp: self.stamp,
+        });
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        while let Some(entry) = self.heap.pop() {
+            match self.live.entry(entry.key) {
+                Occupied(live) if live.get().0 == entry.stamp => {
+                    live.remove();
+                    return Some(entry.item);
+                }
+                _ => continue,
+            }
+        }
+        None
+    }
+
+    fn get(&self, key: &K) -> Option<&T> {
+        self.live.get(key).map(|(_, item)| item)
+    }
+
+    fn clear(&mut self) {
+        self.heap.clear();
+        self.live.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.live.len()
+    }
+}
+
+/// Indexed D-ary heap, replacing an item moves it in place so every key is
+/// in the heap at most once
+///
+/// A wider heap is shallower, which makes pushes and decrease-key cheaper
+/// at the price of more comparisons per pop
+pub struct DaryHeap<K, T, const D: usize = 4> {
+    items: Vec<(K, T)>,
+    /// index of every key in items
+    index: HashMap<K, usize>,
+}
+
+impl<K, T: Ord, const D: usize> DaryHeap<K, T, D> {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<K, T: Ord, const D: usize> Default for DaryHeap<K, T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T, const D: usize> DaryHeap<K, T, D>
+where
+    K: Hash + Eq + Clone,
+    T: Ord,
+{
+    fn swap(&mut self, a: usize, b: usize) {
+        self.items.swap(a, b);
+        *self.index.get_mut(&self.items[a].0).unwrap() = a;
+        *self.index.get_mut(&self.items[b].0).unwrap() = b;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / D;
+            if self.items[parent].1.cmp(&self.items[i].1).is_ge() {
+                break;
+            }
+            self.swap(parent, i);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let first = i * D + 1;
+            let greatest = (first..(first + D).min(self.items.len())).max_by(|a, b| self.items[*a].1.cmp(&self.items[*b].1));
+            match greatest {
+                Some(child) if self.items[child].1.cmp(&self.items[i].1).is_gt() => {
+                    self.swap(i, child);
+                    i = child;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+impl<K, T, const D: usize> OpenList<K, T> for DaryHeap<K, T, D>
+where
+    K: Hash + Eq + Clone,
+    T: Ord,
+{
+    fn push(&mut self, key: K, item: T) -> Result<(), OpenListError> {
+        match self.index.entry(key) {
+            Occupied(entry) => {
+                let i = *entry.get();
+                let raised = item.cmp(&self.items[i].1).is_gt();
+                self.items[i].1 = item;
+                if raised {
+                    self.sift_up(i);
+                } else {
+                    self.sift_down(i);
+                }
+            }
+            Vacant(entry) => {
+                let i = self.items.len();
+                self.items.push((entry.key().clone(), item));
+                entry.insert(i);
+                self.sift_up(i);
+            }
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let last = self.items.len() - 1;
+        self.swap(0, last);
+        let (key, item) = self.items.pop()?;
+        self.index.remove(&key);
+        self.sift_down(0);
+        Some(item)
+    }
+
+    fn get(&self, key: &K) -> Option<&T> {
+        self.index.get(key).map(|i| &self.items[*i].1)
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.index.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+struct PairingNode<K, T> {
+    entry: Option<(K, T)>,
+    child: Option<usize>,
+    next: Option<usize>,
+    /// parent for the first child, previous sibling for the others
+    prev: Option<usize>,
+}
+
+/// Pairing heap, pushes and raising an item are O(1) and the work is
+/// deferred to the pops
+///
+/// Nodes live in a Vec and freed slots are reused, so a search doesn't
+/// allocate once the heap has grown
+pub struct PairingHeap<K, T> {
+    nodes: Vec<PairingNode<K, T>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    /// node of every key
+    index: HashMap<K, usize>,
+}
+
+impl<K, T: Ord> PairingHeap<K, T> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<K, T: Ord> Default for PairingHeap<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T> PairingHeap<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Ord,
+{
+    fn item(&self, node: usize) -> &T {
+        &self.nodes[node].entry.as_ref().unwrap().1
+    }
+
+    /// Joins two trees, the lesser root becomes the first child of the other
+    fn meld(&mut self, a: usize, b: usize) -> usize {
+        let (root, child) = if self.item(a).cmp(self.item(b)).is_ge() { (a, b) } else { (b, a) };
+        if let Some(first) = self.nodes[root].child {
+            self.nodes[first].prev = Some(child);
+        }
+        self.nodes[child].next = self.nodes[root].child;
+        self.nodes[child].prev = Some(root);
+        self.nodes[root].child = Some(child);
+        root
+    }
+
+    fn meld_into_root(&mut self, node: usize) {
+        self.root = Some(match self.root {
+            Some(root) => self.meld(root, node),
+            None => node,
+        });
+    }
+
+    /// Joins a list of siblings into one tree, pairing them left to right
+    /// then melding the pairs right to left
+    fn merge_pairs(&mut self, first: Option<usize>) -> Option<usize> {
+        let mut pairs = vec![];
+        let mut cur = first;
+        while let Some(a) = cur {
+            let b = self.nodes[a].next;
+            self.detach(a);
+            match b {
+                Some(b) => {
+                    cur = self.nodes[b].next;
+                    self.detach(b);
+                    pairs.push(self.meld(a, b));
+                }
+                None => {
+                    cur = None;
+                    pairs.push(a);
+                }
+            }
+        }
+        pairs.into_iter().rev().reduce(|tree, pair| self.meld(pair, tree))
+    }
+
+    fn detach(&mut self, node: usize) {
+        self.nodes[node].prev = None;
+        self.nodes[node].next = None;
+    }
+
+    /// Takes the tree under node out of its parent
+    fn cut(&mut self, node: usize) {
+        let (prev, next) = (self.nodes[node].prev, self.nodes[node].next);
+        if let Some(prev) = prev {
+            if self.nodes[prev].child == Some(node) {
+                self.nodes[prev].child = next;
+            } else {
+                self.nodes[prev].next = next;
+            }
+        }
+        if let Some(next) = next {
+            self.nodes[next].prev = prev;
+        }
+        self.detach(node);
+    }
+}
+
+impl<K, T> OpenList<K, T> for PairingHeap<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Ord,
+{
+    fn push(&mut self, key: K, item: T) -> Result<(), OpenListError> {
+        let node = match self.index.get(&key) {
+            Some(&node) => {
+                let lowered = item.cmp(self.item(node)).is_lt();
+                self.nodes[node].entry = Some((key, item));
+                if self.root != Some(node) {
+                    self.cut(node);
+                } else if !lowered {
+                    return Ok(());
+                } else {
+                    self.root = None;
+                }
+                // the children may now be greater than their parent
+                if lowered {
+                    let child = self.nodes[node].child.take();
+                    if let Some(tree) = self.merge_pairs(child) {
+                        self.meld_into_root(tree);
+                    }
+                }
+                node
+            }
+            None => {
+                let new = PairingNode {
+                    entry: Some((key.clone(), item)),
+                    child: None,
+                    next: None,
+                    prev: None,
+                };
+                let node = match self.free.pop() {
+                    Some(node) => {
+                        self.nodes[node] = new;
+                        node
+                    }
+                    None => {
+                        self.nodes.push(new);
+                        self.nodes.len() - 1
+                    }
+                };
+                self.index.insert(key, node);
+                node
+            }
+        };
+        self.meld_into_root(node);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let root = self.root?;
+        let child = self.nodes[root].child.take();
+        self.root = self.merge_pairs(child);
+        self.free.push(root);
+        let (key, item) = self.nodes[root].entry.take()?;
+        self.index.remove(&key);
+        Some(item)
+    }
+
+    fn get(&self, key: &K) -> Option<&T> {
+        self.index.get(key).map(|node| self.item(*node))
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+        self.index.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+}
+
+/// Integer like cost, mapped to a u64 in the same order
+pub trait RadixCost {
+    fn radix(&self) -> u64;
+}
+
+macro_rules! radix_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixCost for $t {
+            #[inline]
+            fn radix(&self) -> u64 {
+                *self as u64
+            }
+        }
+    )*};
+}
+macro_rules! radix_signed {
+    ($($t:ty),*) => {$(
+        impl RadixCost for $t {
+            #[inline]
+            fn radix(&self) -> u64 {
+                // flipping the sign bit puts the negatives first
+                (*self as i64 as u64) ^ (1 << 63)
+            }
+        }
+    )*};
+}
+radix_unsigned!(u8, u16, u32, u64, usize);
+radix_signed!(i8, i16, i32, i64, isize);
+
+macro_rules! radix_fixed {
+    ($($t:ident $le:ident),*) => {$(
+        impl<Frac: fixed::types::extra::$le> RadixCost for fixed::$t<Frac> {
+            #[inline]
+            fn radix(&self) -> u64 {
+                self.to_bits().radix()
+            }
+        }
+    )*};
+}
+radix_fixed!(FixedI16 LeEqU16, FixedI32 LeEqU32, FixedI64 LeEqU64, FixedU16 LeEqU16, FixedU32 LeEqU32, FixedU64 LeEqU64);
+
+/// Radix heap over nodes with an integer f
+///
+/// Made for searches where the popped f never decreases, like A* with a
+/// consistent heuristic. Nodes sit in a bucket per highest bit differing
+/// from the last popped f, so each one is only moved to a lower bucket a
+/// few times. A node pushed below the last popped f still comes out in
+/// order, it's just kept in the small heap of the current f
+pub struct RadixHeap<K, T> {
+    /// nodes at or below the last popped f, in order
+    current: BinaryHeap<Stamped<K, T>>,
+    /// nodes whose f differs from the last popped one first at bit i - 1
+    buckets: Vec<Vec<Stamped<K, T>>>,
+    last: u64,
+    /// stamp of the live entry of every key and its node
+    live: HashMap<K, (u64, T)>,
+    stamp: u64,
+}
+
+impl<K, T: Ord> RadixHeap<K, T> {
+    pub fn new() -> Self {
+        Self {
+            current: BinaryHeap::new(),
+            buckets: (0..=64).map(|_| Vec::new()).collect(),
+            last: 0,
+            live: HashMap::new(),
+            stamp: 0,
+        }
+    }
+}
+
+impl<K, T: Ord> Default for RadixHeap<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, F: RadixCost, Pos, T> RadixHeap<K, Node<F, Pos, T>>
+where
+    F: Ord,
+    T: Ord,
+{
+    fn insert(&mut self, entry: Stamped<K, Node<F, Pos, T>>) {
+        let radix = entry.item.f.radix();
+        if radix <= self.last {
+            self.current.push(entry);
+        } else {
+            let bucket = 64 - (radix ^ self.last).leading_zeros() as usize;
+            self.buckets[bucket].push(entry);
+        }
+    }
+}
+
+impl<Pos, F, T> OpenList<Pos, Node<F, Pos, T>> for RadixHeap<Pos, Node<F, Pos, T>>
+where
+    Pos: Hash + Eq + Clone,
+    F: RadixCost + Ord + Clone,
+    T: Ord + Clone,
+{
+    fn push(&mut self, key: Pos, item: Node<F, Pos, T>) -> Result<(), OpenListError> {
+        self.stamp += 1;
+        self.live.insert(key.clone(), (self.stamp, item.clone()));
+        self.insert(Stamped {
+            item,
+            key,
+            stamp: self.stamp,
+        });
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Node<F, Pos, T>> {
+        loop {
+            while let Some(entry) = self.current.pop() {
+                match self.live.entry(entry.key) {
+                    Occupied(live) if live.get().0 == entry.stamp => {
+                        live.remove();
+                        return Some(entry.item);
+                    }
+                    _ => continue,
+                }
+            }
+            // spread the first non empty bucket over the lower ones
+            let bucket = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+            let mut entries = std::mem::take(&mut self.buckets[bucket]);
+            entries.retain(|entry| self.live.get(&entry.key).is_some_and(|live| live.0 == entry.stamp));
+            if let Some(min) = entries.iter().map(|entry| entry.item.f.radix()).min() {
+                self.last = min;
+            }
+            for entry in entries.drain(..) {
+                self.insert(entry);
+            }
+            // keep the allocation around
+            self.buckets[bucket] = entries;
+        }
+    }
+
+    fn get(&self, key: &Pos) -> Option<&Node<F, Pos, T>> {
+        self.live.get(key).map(|(_, item)| item)
+    }
+
+    fn clear(&mut self) {
+        self.current.clear();
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.last = 0;
+        self.live.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.live.len()
+    }
+}
+
+/// Order of the nodes with the same f in a BucketQueue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BucketOrder {
+    /// First pushed first
+    #[default]
+    Fifo,
+    /// Last pushed first
+    Lifo,
+}
+
+/// Circular bucket queue over nodes with an integer f, Dial's algorithm
+///
+/// There's a bucket for every f within `span` of the lowest one, so pushes
+/// and pops are O(1) as long as the f of the open nodes stay close. For
+/// Dijkstra that's the largest edge cost, for A* with a consistent
+/// heuristic on an undirected graph twice that. Pushing a node further
+/// away fails with OutOfSpan. Nodes with the same f come out in `order`,
+/// the tie break of the solver isn't used
+pub struct BucketQueue<K, T> {
+    buckets: Vec<VecDeque<Stamped<K, T>>>,
+    order: BucketOrder,
+    /// no open node has a lower f
+    low: u64,
+    /// no open node has a higher f
+    high: u64,
+    /// stamp of the live entry of every key and its node
+    live: HashMap<K, (u64, T)>,
+    stamp: u64,
+}
+
+impl<K, F, Pos, T> BucketQueue<K, Node<F, Pos, T>>
+where
+    F: RadixCost + Default,
+{
+    pub fn new(span: F, order: BucketOrder) -> Self {
+        let span = span.radix().saturating_sub(F::default().radix()) as usize;
+        Self {
+            buckets: (0..=span).map(|_| VecDeque::new()).collect(),
+            order,
+            low: 0,
+            high: 0,
+            live: HashMap::new(),
+            stamp: 0,
+        }
+    }
+}
+
+impl<K, T> BucketQueue<K, T> {
+    pub fn order(&self) -> BucketOrder {
+        self.order
+    }
+}
+
+impl<Pos, F, T> OpenList<Pos, Node<F, Pos, T>> for BucketQueue<Pos, Node<F, Pos, T>>
+where
+    Pos: Hash + Eq + Clone,
+    F: RadixCost + Clone,
+    T: Clone,
+{
+    fn push(&mut self, key: Pos, item: Node<F, Pos, T>) -> Result<(), OpenListError> {
+        let radix = item.f.radix();
+        let (low, high) = if self.live.is_empty() {
+            (radix, radix)
+        } else {
+            (self.low.min(radix), self.high.max(radix))
+        };
+        if high - low >= self.buckets.len() as u64 {
+            return Err(OpenListError::OutOfSpan);
+        }
+        (self.low, self.high) = (low, high);
+
+        self.stamp += 1;
+        self.live.insert(key.clone(), (self.stamp, item.clone()));
+        let bucket = (radix % self.buckets.len() as u64) as usize;
+        self.buckets[bucket].push_back(Stamped {
+            item,
+            key,
+            stamp: self.stamp,
+        });
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Node<F, Pos, T>> {
+        while !self.live.is_empty() {
+            let len = self.buckets.len() as u64;
+            let bucket = &mut self.buckets[(self.low % len) as usize];
+            let entry = match self.order {
+                BucketOrder::Fifo => bucket.pop_front(),
+                BucketOrder::Lifo => bucket.pop_back(),
+            };
+            // what's left in the bucket of the lowest f is either live with
+            // that f or replaced, since the open nodes span less than a lap
+            let entry = match entry {
+                Some(entry) => entry,
+                None => {
+                    self.low += 1;
+                    continue;
+                }
+            };
+            match self.live.entry(entry.key) {
+                Occupied(live) if live.get().0 == entry.stamp => {
+                    live.remove();
+                    return Some(entry.item);
+                }
+                _ => continue,
+            }
+        }
+        None
+    }
+
+    fn get(&self, key: &Pos) -> Option<&Node<F, Pos, T>> {
+        self.live.get(key).map(|(_, item)| item)
+    }
+
+    fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(VecDeque::clear);
+        self.live.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.live.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ordered by priority only, so items under different keys tie
+    ///
+    /// Like Node, which only compares f and leaves the tie break to Ord,
+    /// the partial order is coarser, so an open list using the operators
+    /// instead of `cmp` pops in the wrong order
+    #[derive(Clone, Copy, Debug)]
+    struct Item {
+        priority: u32,
+        key: u32,
+    }
+
+    impl PartialEq for Item {
+        fn eq(&self, other: &Self) -> bool {
+            self.priority / 4 == other.priority / 4
+        }
+    }
+    impl Eq for Item {}
+    impl PartialOrd for Item {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            (self.priority / 4).partial_cmp(&(other.priority / 4))
+        }
+    }
+    impl Ord for Item {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.priority.cmp(&other.priority)
+        }
+    }
+
+    /// std BinaryHeap with the priority of every live key on the side,
+    /// what the open lists are checked against
+    #[derive(Default)]
+    struct Reference {
+        heap: BinaryHeap<(u32, u32)>,
+        live: HashMap<u32, u32>,
+    }
+
+    impl Reference {
+        fn push(&mut self, key: u32, priority: u32) {
+            self.heap.push((priority, key));
+            self.live.insert(key, priority);
+        }
+        /// Greatest live priority
+        fn peek(&mut self) -> Option<u32> {
+            while let Some((priority, key)) = self.heap.peek() {
+                if self.live.get(key) == Some(priority) {
+                    return Some(*priority);
+                }
+                self.heap.pop();
+            }
+            None
+        }
+        /// Checks a pop of the open list, ties may come out in any order
+        /// but every key only once and with its last priority
+        fn pop(&mut self, item: Option<Item>) {
+            assert_eq!(item.map(|item| item.priority), self.peek());
+            if let Some(item) = item {
+                assert_eq!(self.live.remove(&item.key), Some(item.priority));
+            }
+        }
+    }
+
+    /// Runs the pushes, popping on every `None`, checking every pop, get and
+    /// len against the reference, then empties both
+    fn check(open: &mut impl OpenList<u32, Item>, ops: &[Option<(u32, u32)>]) {
+        let mut reference = Reference::default();
+        for op in ops {
+            match *op {
+                Some((key, priority)) => {
+                    open.push(key, Item { priority, key }).unwrap();
+                    reference.push(key, priority);
+                }
+                None => reference.pop(open.pop()),
+            }
+            assert_eq!(open.len(), reference.live.len());
+            for (key, priority) in &reference.live {
+                assert_eq!(open.get(key).map(|item| (item.key, item.priority)), Some((*key, *priority)));
+            }
+        }
+        while !reference.live.is_empty() {
+            reference.pop(open.pop());
+        }
+        assert!(open.is_empty());
+        assert!(open.pop().is_none());
+    }
+
+    fn ties() -> Vec<Option<(u32, u32)>> {
+        let mut ops: Vec<_> = (0..30).map(|key| Some((key, key % 3))).collect();
+        ops.extend([None, None, Some((30, 2)), None, Some((31, 0)), None, None]);
+        ops
+    }
+
+    /// Priorities raised and lowered under the same keys, before and after
+    /// some pops
+    fn decrease_key() -> Vec<Option<(u32, u32)>> {
+        let mut ops: Vec<_> = (0..20).map(|key| Some((key, 100 + key))).collect();
+        ops.extend((0..20).step_by(2).map(|key| Some((key, 200 + key))));
+        ops.extend((1..20).step_by(3).map(|key| Some((key, key))));
+        ops.extend([None, None, Some((19, 300)), Some((0, 0)), None, Some((0, 500)), None]);
+        ops.extend((0..10).map(|key| Some((key, 150))));
+        ops
+    }
+
+    /// Mixed pushes and pops from a fixed seed
+    fn random() -> Vec<Option<(u32, u32)>> {
+        let mut state = 0x2545f4914f6cdd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..2000)
+            .map(|_| match next() % 4 {
+                0 => None,
+                _ => Some(((next() % 64) as u32, (next() % 16) as u32)),
+            })
+            .collect()
+    }
+
+    fn check_all(open: &mut impl OpenList<u32, Item>) {
+        for ops in [ties(), decrease_key(), random()] {
+            check(open, &ops);
+        }
+    }
+
+    fn check_clear(open: &mut impl OpenList<u32, Item>) {
+        for key in 0..10 {
+            open.push(key, Item { priority: key, key }).unwrap();
+        }
+        open.pop();
+        open.clear();
+        assert!(open.is_empty());
+        assert!(open.get(&3).is_none());
+        assert!(open.pop().is_none());
+        // still works after a clear, with keys it held before
+        check(open, &decrease_key());
+    }
+
+    #[test]
+    fn lazy_heap() {
+        check_all(&mut LazyHeap::new());
+        check_clear(&mut LazyHeap::new());
+    }
+
+    #[test]
+    fn dary_heap() {
+        check_all(&mut DaryHeap::<_, _>::new());
+        check_all(&mut DaryHeap::<_, _, 2>::new());
+        check_all(&mut DaryHeap::<_, _, 8>::new());
+        check_clear(&mut DaryHeap::<_, _>::new());
+    }
+
+    #[test]
+    fn pairing_heap() {
+        check_all(&mut PairingHeap::new());
+        check_clear(&mut PairingHeap::new());
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use num_traits::ToPrimitive;
+use ordered_float::OrderedFloat;
+
+use super::{Goal, Movements, Node, PathResult, PathfinderGen};
+
+/// Inverse of the potential `(C - g) / h`, lower is better so the min-heap
+/// of Node pops the highest potential first. It's computed in f64 as the
+/// product of h and the slack quickly overflows the cost type. Nodes with
+/// h = 0 come first, whatever their slack, even when it's 0 too
+#[inline]
+fn key<F: ToPrimitive>(h: F, slack: F) -> OrderedFloat<f64> {
+    let h = h.to_f64().unwrap_or_default();
+    if h <= 0.0 {
+        return OrderedFloat(0.0);
+    }
+    OrderedFloat(h / slack.to_f64().unwrap_or_default())
+}
+
+/// Potential Search, finds any path costing at most `max_cost` fast
+///
+/// Nodes are expanded by their potential `(C - g) / h` instead of f, the
+/// most likely to lead to a path within the bound first. Nodes which can't
+/// make it under the bound are pruned and closed ones are reopened when
+/// reached for less. `max_cost` has to be positive
+pub struct AStar<F, Pos, G, M> {
+    start: Pos,
+    goal: G,
+    movements: M,
+    max_cost: F,
+    heap: BinaryHeap<Node<OrderedFloat<f64>, Pos, usize>>,
+    /// best cost so far to every position and its node
+    best: HashMap<Pos, (F, usize)>,
+    /// position and parent of every node
+    nodes: Vec<(Pos, Option<usize>)>,
+    /// node closest to the goal by heuristic
+    closest: (F, usize),
+}
+
+impl<F, Pos, G, M> AStar<F, Pos, G, M>
+where
+    Pos: Clone,
+{
+    fn path(&self, mut index: usize) -> Vec<Pos> {
+        let mut v = vec![self.nodes[index].0.clone()];
+        while let Some(parent) = self.nodes[index].1 {
+            v.push(self.nodes[parent].0.clone());
+            index = parent;
+        }
+        v.reverse();
+        v
+    }
+}
+
+impl<F, Pos, G, M> PathfinderGen for AStar<F, Pos, G, M>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F>
+        + core::ops::Sub<F, Output = F>
+        + ToPrimitive
+        + Ord
+        + Default
+        + Clone,
+    G: Goal<F, Pos>,
+    M: Movements<F, Pos>,
+{
+    type F = F;
+    type Pos = Pos;
+    type Movements = M;
+    type Goal = G;
+
+    fn new(size: usize, max_cost: Self::F, start: Self::Pos, goal: Self::Goal, movements: Self::Movements) -> Self {
+        let mut potential = Self {
+            start,
+            closest: (goal.heuristic(&start), 0),
+            goal,
+            movements,
+            max_cost,
+            heap: BinaryHeap::new(),
+            best: HashMap::with_capacity(size),
+            nodes: Vec::with_capacity(size),
+        };
+        potential.reset();
+        potential
+    }
+
+    fn reset(&mut self) {
+        self.heap.clear();
+        self.best.clear();
+        self.nodes.clear();
+        let h = self.goal.heuristic(&self.start);
+        self.nodes.push((self.start, None));
+        self.best.insert(self.start, (F::default(), 0));
+        self.heap.push(Node {
+            f: key(h.clone(), self.max_cost.clone()),
+            pos: self.start,
+            t: 0,
+        });
+        self.closest = (h, 0);
+    }
+
+    fn compute(&mut self) -> PathResult<Vec<Self::Pos>> {
+        while let Some(node) = self.heap.pop() {
+            let g = match self.best.get(&node.pos) {
+                Some((g, index)) if *index == node.t => g.clone(),
+                _ => continue,
+            };
+            if self.goal.is_reached(&node.pos) {
+                let path = self.path(node.t);
+                self.reset();
+                return PathResult::Complete(path);
+            }
+
+            for (neighbor_pos, cost) in self.movements.get_neighbors(node.pos) {
+                let this_g = g.clone() + cost;
+                let heuristic = self.goal.heuristic(&neighbor_pos);
+                if this_g.clone() + heuristic.clone() > self.max_cost {
+                    continue;
+                }
+                let index = self.nodes.len();
+                match self.best.entry(neighbor_pos) {
+                    Occupied(mut entry) => {
+                        if entry.get().0 <= this_g {
+                            continue;
+                        }
+                        entry.insert((this_g.clone(), index));
+                    }
+                    Vacant(entry) => {
+                        entry.insert((this_g.clone(), index));
+                    }
+                }
+                self.nodes.push((neighbor_pos, Some(node.t)));
+                if heuristic < self.closest.0 {
+                    self.closest = (heuristic.clone(), index);
+                }
+                self.heap.push(Node {
+                    f: key(heuristic, self.max_cost.clone() - this_g),
+                    pos: neighbor_pos,
+                    t: index,
+                });
+            }
+        }
+        PathResult::NoPath(self.path(self.closest.1))
+    }
+}
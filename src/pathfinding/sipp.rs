@@ -0,0 +1,211 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{Goal, Movements, Node};
+
+const ALWAYS_SAFE: [(u32, u32); 1] = [(0, u32::MAX)];
+
+/// Collision free time intervals of every position, time is in ticks
+///
+/// Intervals are inclusive on both ends, an interval ending at `u32::MAX`
+/// never ends. Positions without collisions are always safe
+#[derive(Clone, Debug)]
+pub struct SafeIntervals<Pos> {
+    collisions: HashMap<Pos, Vec<(u32, u32)>>,
+    safe: HashMap<Pos, Vec<(u32, u32)>>,
+}
+
+impl<Pos> Default for SafeIntervals<Pos> {
+    fn default() -> Self {
+        Self {
+            collisions: Default::default(),
+            safe: Default::default(),
+        }
+    }
+}
+
+impl<Pos> SafeIntervals<Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn clear(&mut self) {
+        self.collisions.clear();
+        self.safe.clear();
+    }
+
+    /// Marks pos as occupied from `from` to `to`, both included
+    pub fn add_collision(&mut self, pos: Pos, from: u32, to: u32) {
+        let collisions = self.collisions.entry(pos).or_default();
+        collisions.push((from.min(to), from.max(to)));
+        collisions.sort_unstable();
+
+        let mut safe = vec![];
+        let mut next = Some(0u32);
+        for &(from, to) in collisions.iter() {
+            if let Some(start) = next {
+                if from > start {
+                    safe.push((start, from - 1));
+                }
+            }
+            next = match next {
+                Some(start) if to < start => Some(start),
+                Some(_) => to.checked_add(1),
+                None => None,
+            };
+        }
+        if let Some(start) = next {
+            safe.push((start, u32::MAX));
+        }
+        self.safe.insert(pos, safe);
+    }
+
+    /// Adds the positions an obstacle goes through, each one is
+    /// occupied from its time until the obstacle arrives at the next one
+    pub fn add_trajectory(&mut self, trajectory: &[(Pos, u32)]) {
+        for w in trajectory.windows(2) {
+            self.add_collision(w[0].0, w[0].1, w[1].1);
+        }
+        if let Some(&(pos, t)) = trajectory.last() {
+            self.add_collision(pos, t, t);
+        }
+    }
+
+    #[inline]
+    pub fn intervals(&self, pos: &Pos) -> &[(u32, u32)] {
+        self.safe.get(pos).map_or(&ALWAYS_SAFE, |v| v)
+    }
+}
+
+/// Safe Interval Path Planning
+///
+/// Searches over (position, safe interval) states, the g of a state is the
+/// earliest arrival time in the interval. Movement costs are the durations
+/// of the moves in ticks, collisions are only checked on positions
+pub struct AStar<Pos> {
+    heap: BinaryHeap<Node<u32, (Pos, usize), usize>>,
+    open: HashMap<(Pos, usize), u32>,
+    closed: HashSet<(Pos, usize)>,
+    /// state, arrival time and parent index of every generated node
+    nodes: Vec<(Pos, u32, Option<usize>)>,
+}
+
+impl<Pos> Default for AStar<Pos> {
+    fn default() -> Self {
+        Self {
+            heap: Default::default(),
+            open: Default::default(),
+            closed: Default::default(),
+            nodes: Default::default(),
+        }
+    }
+}
+
+impl<Pos> AStar<Pos> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn clear(&mut self) {
+        self.heap.clear();
+        self.open.clear();
+        self.closed.clear();
+        self.nodes.clear();
+    }
+    fn path(&self, mut idx: usize) -> Vec<(Pos, u32)>
+    where
+        Pos: Clone,
+    {
+        let (pos, t, _) = &self.nodes[idx];
+        let mut v = vec![(pos.clone(), *t)];
+        while let Some(parent) = self.nodes[idx].2 {
+            let (pos, t, _) = &self.nodes[parent];
+            v.push((pos.clone(), *t));
+            idx = parent;
+        }
+        v.reverse();
+        v
+    }
+}
+
+impl<Pos> AStar<Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+{
+    /// Computes the earliest arriving timed path from start at `start_time`
+    ///
+    /// Every position comes with its arrival time, the agent waits at
+    /// a position until it has to leave for the next one. The goal only
+    /// counts as reached in its last interval, where the agent can stay
+    pub fn compute(
+        &mut self,
+        start: Pos,
+        start_time: u32,
+        goal: impl Goal<u32, Pos>,
+        movements: impl Movements<u32, Pos>,
+        intervals: &SafeIntervals<Pos>,
+    ) -> Option<Vec<(Pos, u32)>> {
+        self.clear();
+
+        let interval = intervals
+            .intervals(&start)
+            .iter()
+            .position(|&(from, to)| from <= start_time && start_time <= to)?;
+        self.nodes.push((start, start_time, None));
+        self.open.insert((start, interval), start_time);
+        self.heap.push(Node {
+            f: start_time.saturating_add(goal.heuristic(&start)),
+            pos: (start, interval),
+            t: 0,
+        });
+
+        while let Some(node) = self.heap.pop() {
+            let (pos, interval) = node.pos;
+            let t = match self.open.remove(&node.pos) {
+                Some(t) => t,
+                None => continue,
+            };
+            let end = intervals.intervals(&pos)[interval].1;
+            if goal.is_reached(&pos) && end == u32::MAX {
+                return Some(self.path(node.t));
+            }
+            self.closed.insert(node.pos);
+
+            for (neighbor_pos, duration) in movements.get_neighbors(pos) {
+                let earliest = t.saturating_add(duration);
+                let latest = end.saturating_add(duration);
+                for (i, &(from, to)) in intervals.intervals(&neighbor_pos).iter().enumerate() {
+                    if from > latest || to < earliest {
+                        continue;
+                    }
+                    let state = (neighbor_pos, i);
+                    if self.closed.contains(&state) {
+                        continue;
+                    }
+
+                    let arrival = earliest.max(from);
+                    match self.open.entry(state) {
+                        Occupied(mut entry) => {
+                            if *entry.get() <= arrival {
+                                continue;
+                            }
+                            entry.insert(arrival);
+                        }
+                        Vacant(entry) => {
+                            entry.insert(arrival);
+                        }
+                    }
+                    self.nodes.push((neighbor_pos, arrival, Some(node.t)));
+                    self.heap.push(Node {
+                        f: arrival.saturating_add(goal.heuristic(&neighbor_pos)),
+                        pos: state,
+                        t: self.nodes.len() - 1,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{Goal, Movements, Node};
+
+/// Positions along with their arrival time
+pub type TimedPath<Pos> = Vec<(Pos, u32)>;
+
+/// Positions and moves other agents have claimed, time is in steps
+///
+/// An agent moving from `a` to `b` leaves at `t` and arrives at `t + 1`
+#[derive(Clone, Debug)]
+pub struct ReservationTable<Pos> {
+    vertices: HashSet<(Pos, u32)>,
+    edges: HashSet<(Pos, Pos, u32)>,
+    /// occupied from that time on, e.g. an agent resting at its goal
+    parked: HashMap<Pos, u32>,
+    /// last reserved time of each position
+    last: HashMap<Pos, u32>,
+}
+
+impl<Pos> Default for ReservationTable<Pos> {
+    fn default() -> Self {
+        Self {
+            vertices: Default::default(),
+            edges: Default::default(),
+            parked: Default::default(),
+            last: Default::default(),
+        }
+    }
+}
+
+impl<Pos> ReservationTable<Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.edges.clear();
+        self.parked.clear();
+        self.last.clear();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.edges.is_empty() && self.parked.is_empty()
+    }
+
+    /// Reserves pos at time t
+    pub fn reserve(&mut self, pos: Pos, t: u32) {
+        self.vertices.insert((pos, t));
+        let last = self.last.entry(pos).or_insert(t);
+        *last = (*last).max(t);
+    }
+    /// Reserves the move from `from` to `to` leaving at time t
+    pub fn reserve_edge(&mut self, from: Pos, to: Pos, t: u32) {
+        self.edges.insert((from, to, t));
+    }
+    /// Reserves pos from time t on
+    pub fn park(&mut self, pos: Pos, t: u32) {
+        let parked = self.parked.entry(pos).or_insert(t);
+        *parked = (*parked).min(t);
+    }
+    /// Reserves every step of a timed path, along with the opposite moves
+    /// so nobody swaps places with the agent following it
+    pub fn reserve_path(&mut self, path: &[(Pos, u32)]) {
+        for &(pos, t) in path {
+            self.reserve(pos, t);
+        }
+        for w in path.windows(2) {
+            let ((a, t), (b, _)) = (w[0], w[1]);
+            if a != b {
+                self.reserve_edge(b, a, t);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_free(&self, pos: &Pos, t: u32) -> bool {
+        !self.vertices.contains(&(*pos, t)) && self.parked.get(pos).is_none_or(|&p| t < p)
+    }
+    #[inline]
+    pub fn is_edge_free(&self, from: &Pos, to: &Pos, t: u32) -> bool {
+        !self.edges.contains(&(*from, *to, t))
+    }
+    /// Whether an agent can stay at pos forever starting at time t
+    #[inline]
+    pub fn can_stay(&self, pos: &Pos, t: u32) -> bool {
+        !self.parked.contains_key(pos) && self.last.get(pos).is_none_or(|&l| l < t)
+    }
+}
+
+/// A* over (position, time) states with a wait action
+///
+/// Every move, waiting included, takes one step of time. A position is
+/// only entered if it's free in the reservation table at the arrival time
+pub struct AStar<F, Pos> {
+    heap: BinaryHeap<Node<F, (Pos, u32), usize>>,
+    open: HashMap<(Pos, u32), F>,
+    closed: HashSet<(Pos, u32)>,
+    /// state and parent index of every generated node
+    nodes: Vec<((Pos, u32), Option<usize>)>,
+    wait_cost: F,
+    horizon: u32,
+    window: Option<u32>,
+}
+
+impl<F, Pos> AStar<F, Pos> {
+    /// `horizon` is the number of steps after which the search gives up
+    pub fn new(wait_cost: F, horizon: u32) -> Self {
+        Self {
+            heap: Default::default(),
+            open: Default::default(),
+            closed: Default::default(),
+            nodes: Default::default(),
+            wait_cost,
+            horizon,
+            window: None,
+        }
+    }
+    pub fn set_horizon(&mut self, horizon: u32) {
+        self.horizon = horizon;
+    }
+    /// Only plans `window` steps ahead, the first node that far in time
+    /// is returned as if it reached the goal, the heuristic estimating the rest
+    pub fn set_window(&mut self, window: Option<u32>) {
+        self.window = window;
+    }
+    fn clear(&mut self) {
+        self.heap.clear();
+        self.open.clear();
+        self.closed.clear();
+        self.nodes.clear();
+    }
+    fn path(&self, mut idx: usize) -> Vec<(Pos, u32)>
+    where
+        Pos: Clone,
+    {
+        let mut v = vec![self.nodes[idx].0.clone()];
+        while let Some(parent) = self.nodes[idx].1 {
+            v.push(self.nodes[parent].0.clone());
+            idx = parent;
+        }
+        v.reverse();
+        v
+    }
+}
+
+impl<F, Pos> AStar<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Computes a timed path from start, leaving at `start_time`
+    ///
+    /// The goal only counts as reached once the agent can stay there,
+    /// returns the cost along with the path
+    pub fn compute(
+        &mut self,
+        start: Pos,
+        start_time: u32,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+        reservations: &ReservationTable<Pos>,
+    ) -> Option<(F, Vec<(Pos, u32)>)> {
+        self.clear();
+
+        let end_time = start_time.saturating_add(self.horizon);
+        let window_end = self.window.map(|w| start_time.saturating_add(w));
+        self.nodes.push(((start, start_time), None));
+        self.open.insert((start, start_time), F::default());
+        self.heap.push(Node {
+            f: goal.heuristic(&start),
+            pos: (start, start_time),
+            t: 0,
+        });
+
+        while let Some(node) = self.heap.pop() {
+            let (pos, t) = node.pos;
+            let g = match self.open.remove(&node.pos) {
+                Some(g) => g,
+                None => continue,
+            };
+            if goal.is_reached(&pos) && reservations.can_stay(&pos, t)
+                || window_end.is_some_and(|end| t >= end)
+            {
+                return Some((g, self.path(node.t)));
+            }
+            self.closed.insert(node.pos);
+            if t >= end_time {
+                continue;
+            }
+
+            let wait = (pos, self.wait_cost.clone());
+            for (neighbor_pos, cost) in movements.get_neighbors(pos).into_iter().chain(Some(wait)) {
+                let state = (neighbor_pos, t + 1);
+                if self.closed.contains(&state)
+                    || !reservations.is_free(&neighbor_pos, t + 1)
+                    || !reservations.is_edge_free(&pos, &neighbor_pos, t)
+                {
+                    continue;
+                }
+
+                let this_g = g.clone() + cost;
+                match self.open.entry(state) {
+                    Occupied(mut entry) => {
+                        if *entry.get() <= this_g {
+                            continue;
+                        }
+                        entry.insert(this_g.clone());
+                    }
+                    Vacant(entry) => {
+                        entry.insert(this_g.clone());
+                    }
+                }
+                self.nodes.push((state, Some(node.t)));
+                self.heap.push(Node {
+                    f: this_g + goal.heuristic(&neighbor_pos),
+                    pos: state,
+                    t: self.nodes.len() - 1,
+                });
+            }
+        }
+        None
+    }
+}
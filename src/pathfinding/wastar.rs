@@ -3,15 +3,249 @@ g(p) < h(p)
      ? g(p) + h(p)
      : (g(p) + (2*w - 1) * h(p)) / w */
 
-use std::collections::hash_map::Entry::{Occupied, Vacant};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
 use std::{fmt::Debug, hash::Hash};
 
-use super::{Goal, Movements, Node, Pathfinder};
+use num_traits::{Bounded, NumCast, ToPrimitive};
+
+use super::open_list::{LazyHeap, OpenList, OpenListError};
+use super::{Goal, Movements, Node, PathResult, Pathfinder};
+
+/// Orders the nodes of a weighted A* from their cost so far, heuristic
+/// and depth in the search tree
+pub trait Priority<F> {
+    fn priority(&self, g: F, h: F, depth: u32) -> F;
+    /// Paths found cost at most this times the optimal cost, given an
+    /// admissible heuristic
+    fn bound(&self) -> f64;
+}
+
+/// Bare priority functions, their bound is unknown
+impl<F> Priority<F> for fn(F, F) -> F {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        self(g, h)
+    }
+    fn bound(&self) -> f64 {
+        f64::INFINITY
+    }
+}
+
+#[inline]
+fn float<F: ToPrimitive>(x: F) -> f64 {
+    x.to_f64().unwrap_or_default()
+}
+
+/// Priorities are computed in f64 so fractional weights apply to integer
+/// costs as well, which then round down. A goal's priority is its cost, so
+/// rounding down never lets a path past the bound. Priorities out of range
+/// of the cost type saturate, NaN counts as the highest
+#[inline]
+fn cast<F: NumCast + Bounded>(x: f64) -> F {
+    F::from(x).unwrap_or_else(|| if x < 0.0 { F::min_value() } else { F::max_value() })
+}
+
+/// The weights only bound the cost from 1 up
+fn check_weight(w: f64) -> f64 {
+    assert!(w >= 1.0, "weight {w} is below 1");
+    w
+}
+
+/// Weighted A*, `g + w * h`
+#[derive(Clone, Copy, Debug)]
+pub struct StaticWeight<F> {
+    w: f64,
+    marker: PhantomData<F>,
+}
+
+impl<F> StaticWeight<F> {
+    /// Panics if w is below 1
+    pub fn new(w: f64) -> Self {
+        Self {
+            w: check_weight(w),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for StaticWeight<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        cast(float(g) + self.w * float(h))
+    }
+    fn bound(&self) -> f64 {
+        self.w
+    }
+}
+
+/// Convex downward parabola of Chen and Sturtevant, close to A* near the
+/// start and greedier near the goal, scaled by w so goals come out at cost
+/// `(g + (2w - 1) * h + sqrt((g - h)^2 + 4w * g * h)) / 2`
+#[derive(Clone, Copy, Debug)]
+pub struct Xdp<F> {
+    w: f64,
+    marker: PhantomData<F>,
+}
+
+impl<F> Xdp<F> {
+    /// Panics if w is below 1
+    pub fn new(w: f64) -> Self {
+        Self {
+            w: check_weight(w),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for Xdp<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        let (g, h, w) = (float(g), float(h), self.w);
+        let root = ((g - h) * (g - h) + 4.0 * w * g * h).sqrt();
+        cast((g + (2.0 * w - 1.0) * h + root) / 2.0)
+    }
+    fn bound(&self) -> f64 {
+        self.w
+    }
+}
+
+/// Convex upward parabola of Chen and Sturtevant, greedy near the start
+/// and close to A* near the goal, scaled by w so goals come out at cost
+/// `(g + h + sqrt((g + h)^2 + 4w(w - 1) * h^2)) / 2`
+#[derive(Clone, Copy, Debug)]
+pub struct Xup<F> {
+    w: f64,
+    marker: PhantomData<F>,
+}
+
+impl<F> Xup<F> {
+    /// Panics if w is below 1
+    pub fn new(w: f64) -> Self {
+        Self {
+            w: check_weight(w),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for Xup<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        let (g, h, w) = (float(g), float(h), self.w);
+        let root = ((g + h) * (g + h) + 4.0 * w * (w - 1.0) * h * h).sqrt();
+        cast((g + h + root) / 2.0)
+    }
+    fn bound(&self) -> f64 {
+        self.w
+    }
+}
+
+/// Piecewise linear version of `Xup`, `pxwu` in Chen and Sturtevant,
+/// scaled by w so goals come out at cost
+/// `g < (2w - 1) * h ? w * (g / (2w - 1) + h) : g + h`
+#[derive(Clone, Copy, Debug)]
+pub struct PwXu<F> {
+    w: f64,
+    marker: PhantomData<F>,
+}
+
+impl<F> PwXu<F> {
+    /// Panics if w is below 1
+    pub fn new(w: f64) -> Self {
+        Self {
+            w: check_weight(w),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for PwXu<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        let (g, h, slope) = (float(g), float(h), 2.0 * self.w - 1.0);
+        if g < slope * h {
+            cast(self.w * (g / slope + h))
+        } else {
+            cast(g + h)
+        }
+    }
+    fn bound(&self) -> f64 {
+        self.w
+    }
+}
+
+/// Piecewise linear version of `Xdp`, `pxwd` in Chen and Sturtevant,
+/// scaled by w so goals come out at cost
+/// `g < h ? w * (g + h) : g + (2w - 1) * h`
+#[derive(Clone, Copy, Debug)]
+pub struct PwXd<F> {
+    w: f64,
+    marker: PhantomData<F>,
+}
+
+impl<F> PwXd<F> {
+    /// Panics if w is below 1
+    pub fn new(w: f64) -> Self {
+        Self {
+            w: check_weight(w),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for PwXd<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, _depth: u32) -> F {
+        let (g, h) = (float(g), float(h));
+        if g < h {
+            cast(self.w * (g + h))
+        } else {
+            cast(g + (2.0 * self.w - 1.0) * h)
+        }
+    }
+    fn bound(&self) -> f64 {
+        self.w
+    }
+}
+
+/// Dynamic weighting of Pohl, the weight fades out with the depth
+/// `g + (1 + e * (1 - depth / n)) * h`, plain A* past depth n
+#[derive(Clone, Copy, Debug)]
+pub struct DynamicWeight<F> {
+    epsilon: f64,
+    depth: u32,
+    marker: PhantomData<F>,
+}
+
+impl<F> DynamicWeight<F> {
+    /// `depth` is the expected depth of the solution, panics if epsilon
+    /// is negative
+    pub fn new(epsilon: f64, depth: u32) -> Self {
+        assert!(epsilon >= 0.0, "epsilon {epsilon} is negative");
+        Self {
+            epsilon,
+            depth: depth.max(1),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: NumCast + Bounded> Priority<F> for DynamicWeight<F> {
+    #[inline]
+    fn priority(&self, g: F, h: F, depth: u32) -> F {
+        let fade = self.depth.saturating_sub(depth) as f64 / self.depth as f64;
+        cast(float(g) + (1.0 + self.epsilon * fade) * float(h))
+    }
+    fn bound(&self) -> f64 {
+        1.0 + self.epsilon
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct AStarT<F, Pos> {
     g: F,
+    depth: u32,
     parent: Option<refpool::PoolRef<NodeLeaf<Pos>>>,
 }
 
@@ -37,7 +271,6 @@ where
     F: Ord,
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        println!("got called");
         self.g.cmp(&other.g)
     }
 }
@@ -67,42 +300,65 @@ where
     }
 }
 
-pub struct AStar<F, Pos> {
+pub type AStarNode<F, Pos> = Node<F, Pos, AStarT<F, Pos>>;
+
+pub struct AStar<F, Pos, W = fn(F, F) -> F, O = LazyHeap<Pos, AStarNode<F, Pos>>> {
     closed: HashSet<Pos>,
-    heap: BinaryHeap<Node<F, Pos, AStarT<F, Pos>>>,
-    open: HashMap<Pos, (F, AStarT<F, Pos>)>,
+    open: O,
     refpool: refpool::Pool<NodeLeaf<Pos>>,
-    weights: fn(F, F) -> F,
+    weights: W,
+    marker: PhantomData<F>,
 }
-impl<F, Pos> AStar<F, Pos> {
+impl<F, Pos, W, O> AStar<F, Pos, W, O>
+where
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
     pub fn dbg(&self) {
         dbg!((
             self.refpool.get_max_size() - self.refpool.get_pool_size(),
             self.open.len(),
             self.closed.len(),
-            self.heap.len(),
         ));
     }
 }
-impl<F, Pos> AStar<F, Pos> {
-    pub fn with_refpool_size(size: usize, w: fn(F, F) -> F) -> Self
-    where
-        F: Ord,
-    {
+impl<F, Pos, W> AStar<F, Pos, W>
+where
+    F: Ord,
+{
+    pub fn with_refpool_size(size: usize, w: W) -> Self {
+        Self::with_open_list(size, w, LazyHeap::new())
+    }
+}
+impl<F, Pos, W, O> AStar<F, Pos, W, O> {
+    pub fn with_open_list(size: usize, w: W, open: O) -> Self {
         Self {
             closed: Default::default(),
-            heap: Default::default(),
-            open: Default::default(),
+            open,
             refpool: refpool::Pool::new(size),
             weights: w,
+            marker: PhantomData,
         }
     }
+    pub fn weights(&self) -> &W {
+        &self.weights
+    }
+    /// Suboptimality bound of the paths found
+    pub fn bound(&self) -> f64
+    where
+        W: Priority<F>,
+    {
+        self.weights.bound()
+    }
+}
+impl<F, Pos, W, O> AStar<F, Pos, W, O>
+where
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
     fn clear(&mut self) {
         self.closed.clear();
-        self.heap.clear();
         self.open.clear();
     }
-    fn path(&mut self, end_node: Node<F, Pos, AStarT<F, Pos>>) -> Vec<Pos>
+    fn path(&mut self, end_node: AStarNode<F, Pos>) -> Vec<Pos>
     where
         Pos: Clone,
     {
@@ -115,20 +371,113 @@ impl<F, Pos> AStar<F, Pos> {
     }
 }
 
-impl<F, Pos> Pathfinder for AStar<F, Pos>
+impl<F, Pos, W, O> AStar<F, Pos, W, O>
 where
     Pos: Hash + Eq + Clone + Copy,
-    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    W: Priority<F>,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
 {
-    type F = F;
-    type Pos = Pos;
+    /// Anytime weighted A*, keeps searching after the first path
+    ///
+    /// Every cheaper path is handed to `improved` as it's found, in start
+    /// to goal order. Nodes which can't beat the incumbent are pruned and
+    /// closed nodes are reopened on a cheaper path, so once nothing is left
+    /// open the last path is optimal, given an admissible heuristic.
+    /// Returns Complete with that path, Timeout with the best so far after
+    /// `max_expansions` or when the open list can't take a node, or NoPath
+    pub fn compute_anytime(
+        &mut self,
+        start: Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+        max_expansions: usize,
+        mut improved: impl FnMut(&F, &[Pos]),
+    ) -> PathResult<Option<(F, Vec<Pos>)>> {
+        self.clear();
+        let mut best_g: HashMap<Pos, F> = HashMap::new();
+        best_g.insert(start, F::default());
+        let start_node = Node {
+            f: self.weights.priority(F::default(), goal.heuristic(&start), 0),
+            pos: start,
+            t: AStarT {
+                g: F::default(),
+                depth: 0,
+                parent: None,
+            },
+        };
+        if self.open.push(start, start_node).is_err() {
+            return PathResult::Timeout(None);
+        }
 
-    fn compute(
+        let beaten = |incumbent: &Option<(F, Vec<Pos>)>, f: F| incumbent.as_ref().is_some_and(|(cost, _)| f >= *cost);
+        let mut incumbent: Option<(F, Vec<Pos>)> = None;
+        let mut expanded = 0;
+        while let Some(node) = self.open.pop() {
+            if beaten(&incumbent, node.t.g.clone() + goal.heuristic(&node.pos)) {
+                continue;
+            }
+            if goal.is_reached(&node.pos) {
+                let cost = node.t.g.clone();
+                let mut path = self.path(node);
+                path.reverse();
+                improved(&cost, &path);
+                incumbent = Some((cost, path));
+                continue;
+            }
+            if expanded == max_expansions {
+                return PathResult::Timeout(incumbent);
+            }
+            expanded += 1;
+
+            let parent = refpool::PoolRef::new(&self.refpool, NodeLeaf(node.pos, node.t.parent));
+            for (neighbor_pos, cost) in movements.get_neighbors(node.pos) {
+                let this_g = node.t.g.clone() + cost;
+                if best_g.get(&neighbor_pos).is_some_and(|g| *g <= this_g) {
+                    continue;
+                }
+                let heuristic = goal.heuristic(&neighbor_pos);
+                if beaten(&incumbent, this_g.clone() + heuristic.clone()) {
+                    continue;
+                }
+                best_g.insert(neighbor_pos, this_g.clone());
+                let depth = node.t.depth + 1;
+                let neighbor = Node {
+                    f: self.weights.priority(this_g.clone(), heuristic, depth),
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        g: this_g,
+                        depth,
+                        parent: Some(parent.clone()),
+                    },
+                };
+                if self.open.push(neighbor_pos, neighbor).is_err() {
+                    return PathResult::Timeout(incumbent);
+                }
+            }
+        }
+        match incumbent {
+            Some(_) => PathResult::Complete(incumbent),
+            None => PathResult::NoPath(None),
+        }
+    }
+}
+
+impl<F, Pos, W, O> AStar<F, Pos, W, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    W: Priority<F>,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
+    /// Like compute, but fails when the open list can't take a node
+    /// instead of returning None
+    pub fn try_compute(
         &mut self,
-        start: Self::Pos,
+        start: Pos,
         goal: impl Goal<F, Pos>,
         movements: impl Movements<F, Pos>,
-    ) -> Option<Vec<Self::Pos>> {
+    ) -> Result<Option<Vec<Pos>>, OpenListError> {
         self.clear();
 
         let h = goal.heuristic(&start);
@@ -137,24 +486,24 @@ where
             pos: start,
             t: AStarT {
                 g: F::default(),
+                depth: 0,
                 parent: None,
             },
         };
-        self.heap.push(start_node.clone());
+        self.open.push(start, start_node.clone())?;
 
         let max_cost: F = F::default();
         let mut best_node = start_node;
 
-        while let Some(node) = self.heap.pop() {
+        while let Some(node) = self.open.pop() {
             // println!("{:?}", node);
             // println!("{}", self.refpool.get_pool_size());
             if goal.is_reached(&node.pos) {
                 let res = self.path(node);
                 // self.clear();
-                return Some(res);
+                return Ok(Some(res));
             }
 
-            self.open.remove(&node.pos);
             self.closed.insert(node.pos);
             // self.visited_chunks.insert(node.pos.into());
 
@@ -167,42 +516,50 @@ where
 
                 let this_g = node.t.g.clone() + cost;
                 let heuristic = goal.heuristic(&neighbor_pos);
-                let this_f = (self.weights)(this_g.clone(), heuristic.clone());
+                let depth = node.t.depth + 1;
+                let this_f = self.weights.priority(this_g.clone(), heuristic.clone(), depth);
                 if max_cost > F::default() && this_f.clone() > max_cost {
                     continue;
                 }
 
-                match self.open.entry(neighbor_pos) {
-                    Occupied(mut entry) => {
-                        if entry.get().1.g < this_g {
-                            continue;
-                        }
-                        let node = entry.get_mut();
-                        let (f, t) = node;
-                        *f = this_f.clone();
-                        t.g = this_g.clone();
-                        t.parent = Some(parent.clone());
-                        if this_f - this_g < best_node.f.clone() - best_node.t.g.clone() {
-                            best_node = (neighbor_pos, (f.clone(), t.clone())).into();
-                        }
-                    }
-                    Vacant(entry) => {
-                        let node = entry.insert((
-                            this_f.clone(),
-                            AStarT {
-                                g: this_g.clone(),
-                                parent: Some(parent.clone()),
-                            },
-                        ));
-                        let neighbor = (neighbor_pos, node.clone()).into();
-                        if this_f - this_g < best_node.f.clone() - best_node.t.g.clone() {
-                            best_node = Node::clone(&neighbor);
-                        }
-                        self.heap.push(neighbor);
-                    }
+                if self.open.get(&neighbor_pos).is_some_and(|open| open.t.g <= this_g) {
+                    continue;
+                }
+                let neighbor = Node {
+                    f: this_f.clone(),
+                    pos: neighbor_pos,
+                    t: AStarT {
+                        g: this_g.clone(),
+                        depth,
+                        parent: Some(parent.clone()),
+                    },
                 };
+                if this_f - this_g < best_node.f.clone() - best_node.t.g.clone() {
+                    best_node = Node::clone(&neighbor);
+                }
+                self.open.push(neighbor_pos, neighbor)?;
             }
         }
-        None
+        Ok(None)
+    }
+}
+
+impl<F, Pos, W, O> Pathfinder for AStar<F, Pos, W, O>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + core::ops::Sub<F, Output = F> + Ord + Default + Clone,
+    W: Priority<F>,
+    O: OpenList<Pos, AStarNode<F, Pos>>,
+{
+    type F = F;
+    type Pos = Pos;
+
+    fn compute(
+        &mut self,
+        start: Self::Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+    ) -> Option<Vec<Self::Pos>> {
+        self.try_compute(start, goal, movements).ok()?
     }
 }
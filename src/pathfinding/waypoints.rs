@@ -0,0 +1,187 @@
+use std::hash::Hash;
+
+use super::{path_cost, Goal, Movements, Pathfinder};
+
+/// Goal reached by any of the wrapped goals
+///
+/// The heuristic is the smallest of the wrapped heuristics,
+/// so it stays admissible if all of them are
+#[derive(Clone, Debug)]
+pub struct AnyOf<G>(pub Vec<G>);
+
+impl<F, Pos, G> Goal<F, Pos> for AnyOf<G>
+where
+    G: Goal<F, Pos>,
+    F: Ord + Default,
+{
+    fn is_reached(&self, pos: &Pos) -> bool {
+        self.0.iter().any(|g| g.is_reached(pos))
+    }
+    fn heuristic(&self, pos: &Pos) -> F {
+        self.0
+            .iter()
+            .map(|g| g.heuristic(pos))
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+/// A tour through a set of waypoints
+#[derive(Clone, Debug)]
+pub struct Route<F, Pos> {
+    /// Indices into the waypoints, in visiting order
+    pub order: Vec<usize>,
+    pub cost: F,
+    pub path: Vec<Pos>,
+}
+
+/// Cost and path between two waypoints, None if unreachable
+type Leg<F, Pos> = Option<(F, Vec<Pos>)>;
+
+/// Multi target queries on top of a Pathfinder
+pub struct Router<S> {
+    solver: S,
+}
+
+impl<S> Router<S> {
+    pub fn new(solver: S) -> Self {
+        Self { solver }
+    }
+    pub fn solver(&mut self) -> &mut S {
+        &mut self.solver
+    }
+    pub fn into_inner(self) -> S {
+        self.solver
+    }
+}
+
+impl<S, F, Pos> Router<S>
+where
+    S: Pathfinder<F = F, Pos = Pos>,
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Finds the cheapest of the goals to reach in a single search
+    ///
+    /// Returns the index of the reached goal, the cost and the path
+    pub fn nearest<G>(
+        &mut self,
+        start: Pos,
+        goals: &[G],
+        movements: impl Movements<F, Pos>,
+    ) -> Option<(usize, F, Vec<Pos>)>
+    where
+        G: Goal<F, Pos>,
+    {
+        let any = AnyOf(goals.iter().collect());
+        let mut path = self.solver.compute(start, &any, &movements)?;
+        path.reverse();
+        let end = path.last()?;
+        let idx = goals.iter().position(|g| g.is_reached(end))?;
+        let cost = path_cost(&path, &movements)?;
+        Some((idx, cost, path))
+    }
+
+    /// Visits every waypoint, optionally ending at `end`
+    ///
+    /// The order is picked by nearest neighbor and then improved with 2-opt,
+    /// using the costs of pairwise searches. `to_goal` turns a position into
+    /// the Goal used to search for it
+    pub fn route<G>(
+        &mut self,
+        start: Pos,
+        waypoints: &[Pos],
+        end: Option<Pos>,
+        to_goal: impl Fn(&Pos) -> G,
+        movements: impl Movements<F, Pos>,
+    ) -> Option<Route<F, Pos>>
+    where
+        G: Goal<F, Pos>,
+    {
+        let n = waypoints.len();
+        // sources are start and the waypoints, targets the waypoints and end
+        let sources: Vec<Pos> = Some(start).into_iter().chain(waypoints.iter().copied()).collect();
+        let targets: Vec<Pos> = waypoints.iter().copied().chain(end).collect();
+
+        let mut legs: Vec<Vec<Leg<F, Pos>>> = Vec::with_capacity(sources.len());
+        for (i, from) in sources.iter().enumerate() {
+            let mut row = Vec::with_capacity(targets.len());
+            for (j, to) in targets.iter().enumerate() {
+                if i == j + 1 {
+                    row.push(Some((F::default(), vec![*from])));
+                    continue;
+                }
+                let leg = self
+                    .solver
+                    .compute(*from, to_goal(to), &movements)
+                    .map(|mut path| {
+                        path.reverse();
+                        path
+                    })
+                    .and_then(|path| Some((path_cost(&path, &movements)?, path)));
+                row.push(leg);
+            }
+            legs.push(row);
+        }
+        let cost = |from: usize, to: usize| legs[from][to].as_ref().map(|(c, _)| c.clone());
+        // waypoint k is source k + 1 and target k, end is target n
+        let tour_cost = |order: &[usize]| -> Option<F> {
+            let mut total = F::default();
+            let mut from = 0;
+            for &k in order {
+                total = total + cost(from, k)?;
+                from = k + 1;
+            }
+            if end.is_some() {
+                total = total + cost(from, n)?;
+            }
+            Some(total)
+        };
+
+        let mut order = Vec::with_capacity(n);
+        let mut visited = vec![false; n];
+        let mut from = 0;
+        for _ in 0..n {
+            let next = (0..n)
+                .filter(|&k| !visited[k])
+                .filter_map(|k| Some((cost(from, k)?, k)))
+                .min()?
+                .1;
+            visited[next] = true;
+            order.push(next);
+            from = next + 1;
+        }
+        let mut best = tour_cost(&order)?;
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..n {
+                for j in i + 1..n {
+                    order[i..=j].reverse();
+                    match tour_cost(&order) {
+                        Some(c) if c < best => {
+                            best = c;
+                            improved = true;
+                        }
+                        _ => order[i..=j].reverse(),
+                    }
+                }
+            }
+        }
+
+        let mut path = vec![start];
+        let mut from = 0;
+        let hops = order.iter().copied().chain(end.map(|_| n));
+        for k in hops {
+            let (_, leg) = legs[from][k].as_ref()?;
+            path.extend(leg.iter().skip(1).copied());
+            from = k + 1;
+        }
+        Some(Route {
+            order,
+            cost: best,
+            path,
+        })
+    }
+}
@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::space_time::{self, ReservationTable, TimedPath};
+use super::{Goal, Movements, Node, ReverseMovements};
+
+struct Reverse<F, Pos> {
+    heap: BinaryHeap<Node<F, Pos, ()>>,
+    open: HashMap<Pos, F>,
+    closed: HashMap<Pos, F>,
+    expanded: usize,
+}
+
+/// Goal whose heuristic is the true distance to a position
+///
+/// The distances come from a reverse A* started at the goal, which is
+/// resumed whenever a position it hasn't closed yet gets asked for.
+/// `toward` guides it to the agent's start. Once `max_expansions` is
+/// used up, unknown positions get a heuristic of `F::default()`
+pub struct TrueDistance<F, Pos, M, H> {
+    goal: Pos,
+    toward: H,
+    movements: M,
+    max_expansions: usize,
+    state: RefCell<Reverse<F, Pos>>,
+}
+
+impl<F, Pos, M, H> TrueDistance<F, Pos, M, H>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    M: ReverseMovements<F, Pos>,
+    H: Goal<F, Pos>,
+{
+    pub fn new(goal: Pos, toward: H, movements: M, max_expansions: usize) -> Self {
+        let mut state = Reverse {
+            heap: BinaryHeap::new(),
+            open: HashMap::new(),
+            closed: HashMap::new(),
+            expanded: 0,
+        };
+        state.open.insert(goal, F::default());
+        state.heap.push(Node {
+            f: toward.heuristic(&goal),
+            pos: goal,
+            t: (),
+        });
+        Self {
+            goal,
+            toward,
+            movements,
+            max_expansions,
+            state: RefCell::new(state),
+        }
+    }
+
+    /// Cost of the cheapest path from pos to the goal, if found
+    pub fn distance(&self, pos: &Pos) -> Option<F> {
+        let mut state = self.state.borrow_mut();
+        if let Some(g) = state.closed.get(pos) {
+            return Some(g.clone());
+        }
+        while state.expanded < self.max_expansions {
+            let node = state.heap.pop()?;
+            let g = match state.open.remove(&node.pos) {
+                Some(g) => g,
+                None => continue,
+            };
+            state.closed.insert(node.pos, g.clone());
+            state.expanded += 1;
+
+            for (pred, cost) in self.movements.get_predecessors(node.pos) {
+                if state.closed.contains_key(&pred) {
+                    continue;
+                }
+                let this_g = g.clone() + cost;
+                match state.open.entry(pred) {
+                    Occupied(mut entry) => {
+                        if *entry.get() <= this_g {
+                            continue;
+                        }
+                        entry.insert(this_g.clone());
+                    }
+                    Vacant(entry) => {
+                        entry.insert(this_g.clone());
+                    }
+                }
+                let f = this_g + self.toward.heuristic(&pred);
+                state.heap.push(Node {
+                    f,
+                    pos: pred,
+                    t: (),
+                });
+            }
+            if node.pos == *pos {
+                return Some(g);
+            }
+        }
+        None
+    }
+}
+
+impl<F, Pos, M, H> Goal<F, Pos> for TrueDistance<F, Pos, M, H>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+    M: ReverseMovements<F, Pos>,
+    H: Goal<F, Pos>,
+{
+    fn is_reached(&self, pos: &Pos) -> bool {
+        *pos == self.goal
+    }
+    fn heuristic(&self, pos: &Pos) -> F {
+        self.distance(pos).unwrap_or_default()
+    }
+}
+
+/// Windowed Hierarchical Cooperative A*
+///
+/// Agents plan one after the other, in the given priority order, against a
+/// reservation table shared over a window of time. Only the first `commit`
+/// steps of every plan are kept before everyone replans. An agent which
+/// can't find a path within the window waits in place, and one which is
+/// done early waits at its goal. If such a wait runs into an agent planned
+/// before it there's no way around and the search fails
+pub struct Whca<F, Pos> {
+    low: space_time::AStar<F, Pos>,
+    table: ReservationTable<Pos>,
+    window: u32,
+    commit: u32,
+    max_expansions: usize,
+}
+
+impl<F, Pos> Whca<F, Pos> {
+    /// `commit` is clamped to the window, `max_expansions` bounds every
+    /// reverse search used for the heuristics
+    pub fn new(wait_cost: F, window: u32, commit: u32, max_expansions: usize) -> Self {
+        let window = window.max(1);
+        let mut low = space_time::AStar::new(wait_cost, window);
+        low.set_window(Some(window));
+        Self {
+            low,
+            table: Default::default(),
+            window,
+            commit: commit.clamp(1, window),
+            max_expansions,
+        }
+    }
+}
+
+impl<F, Pos> Whca<F, Pos>
+where
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Moves every (start, goal) agent to its goal, giving up after
+    /// `max_time` steps or on a wait into another agent. `to_goal` turns a
+    /// start into the Goal guiding the reverse searches
+    pub fn compute<M, H>(
+        &mut self,
+        agents: &[(Pos, Pos)],
+        movements: M,
+        to_goal: impl Fn(&Pos) -> H,
+        max_time: u32,
+    ) -> Option<Vec<TimedPath<Pos>>>
+    where
+        M: Movements<F, Pos> + ReverseMovements<F, Pos>,
+        H: Goal<F, Pos>,
+    {
+        let heuristics: Vec<_> = agents
+            .iter()
+            .map(|(start, goal)| {
+                TrueDistance::new(*goal, to_goal(start), &movements, self.max_expansions)
+            })
+            .collect();
+        let mut paths: Vec<TimedPath<Pos>> = agents.iter().map(|(s, _)| vec![(*s, 0)]).collect();
+
+        let mut now = 0;
+        while now < max_time {
+            if paths
+                .iter()
+                .zip(agents)
+                .all(|(path, (_, goal))| path.last().unwrap().0 == *goal)
+            {
+                break;
+            }
+
+            self.table.clear();
+            let end = now + self.window;
+            let mut plans = Vec::with_capacity(agents.len());
+            for (path, heuristic) in paths.iter().zip(&heuristics) {
+                let pos = path.last().unwrap().0;
+                let mut plan = match self.low.compute(pos, now, heuristic, &movements, &self.table) {
+                    Some((_, plan)) => plan,
+                    None => vec![(pos, now)],
+                };
+                let (last, t) = *plan.last().unwrap();
+                plan.extend((t + 1..=end).map(|t| (last, t)));
+                // the low level plan avoids the table, the waits don't
+                if plan.iter().any(|(pos, t)| !self.table.is_free(pos, *t)) {
+                    return None;
+                }
+                self.table.reserve_path(&plan);
+                plans.push(plan);
+            }
+
+            for (path, plan) in paths.iter_mut().zip(plans) {
+                path.extend(plan.into_iter().skip(1).take(self.commit as usize));
+            }
+            now += self.commit;
+        }
+
+        let mut done = true;
+        for (path, (_, goal)) in paths.iter_mut().zip(agents) {
+            let last = path.last().unwrap().0;
+            done &= last == *goal;
+            while path.len() > 1 && path[path.len() - 2].0 == last {
+                path.pop();
+            }
+        }
+        done.then_some(paths)
+    }
+}
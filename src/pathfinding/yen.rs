@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use super::{path_cost, Goal, Movements, Pathfinder};
+
+/// Movements with some positions and edges temporarily removed
+#[derive(Clone, Debug)]
+pub struct Filtered<M, Pos> {
+    pub movements: M,
+    pub nodes: HashSet<Pos>,
+    pub edges: HashSet<(Pos, Pos)>,
+}
+
+impl<M, Pos> Filtered<M, Pos> {
+    pub fn new(movements: M) -> Self {
+        Self {
+            movements,
+            nodes: Default::default(),
+            edges: Default::default(),
+        }
+    }
+}
+
+impl<F, Pos, M> Movements<F, Pos> for Filtered<M, Pos>
+where
+    Pos: Hash + Eq + Clone,
+    M: Movements<F, Pos>,
+{
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, F)> {
+        if self.nodes.contains(&pos) {
+            return vec![];
+        }
+        let mut neighbors = self.movements.get_neighbors(pos.clone());
+        neighbors.retain(|(n, _)| {
+            !self.nodes.contains(n) && !self.edges.contains(&(pos.clone(), n.clone()))
+        });
+        neighbors
+    }
+}
+
+/// Yen's algorithm, finds the K cheapest loopless paths
+///
+/// Every spur path is a regular query on the wrapped solver with the
+/// root path and the already used edges filtered out of the movements
+pub struct KShortest<S> {
+    solver: S,
+}
+
+impl<S> KShortest<S> {
+    pub fn new(solver: S) -> Self {
+        Self { solver }
+    }
+    pub fn solver(&mut self) -> &mut S {
+        &mut self.solver
+    }
+    pub fn into_inner(self) -> S {
+        self.solver
+    }
+}
+
+impl<S, F, Pos> KShortest<S>
+where
+    S: Pathfinder<F = F, Pos = Pos>,
+    Pos: Hash + Eq + Clone + Copy,
+    F: core::ops::Add<F, Output = F> + Ord + Default + Clone,
+{
+    /// Returns up to k paths with their cost, cheapest first
+    pub fn compute(
+        &mut self,
+        start: Pos,
+        goal: impl Goal<F, Pos>,
+        movements: impl Movements<F, Pos>,
+        k: usize,
+    ) -> Vec<(F, Vec<Pos>)> {
+        let mut found: Vec<(F, Vec<Pos>)> = vec![];
+        if k == 0 {
+            return found;
+        }
+        let first = match self.solver.compute(start, &goal, &movements) {
+            Some(mut path) => {
+                path.reverse();
+                path
+            }
+            None => return found,
+        };
+        match path_cost(&first, &movements) {
+            Some(cost) => found.push((cost, first)),
+            None => return found,
+        }
+
+        let mut candidates: Vec<(F, Vec<Pos>)> = vec![];
+        let mut filtered = Filtered::new(&movements);
+        while found.len() < k {
+            let prev = found.last().unwrap().1.clone();
+
+            for i in 0..prev.len() - 1 {
+                let spur = prev[i];
+                let root = &prev[..=i];
+
+                filtered.nodes.clear();
+                filtered.nodes.extend(root[..i].iter().copied());
+                filtered.edges.clear();
+                for (_, path) in found.iter() {
+                    if path.len() > i + 1 && &path[..=i] == root {
+                        filtered.edges.insert((path[i], path[i + 1]));
+                    }
+                }
+
+                let spur_path = match self.solver.compute(spur, &goal, &filtered) {
+                    Some(mut path) => {
+                        path.reverse();
+                        path
+                    }
+                    None => continue,
+                };
+                let mut path = root[..i].to_vec();
+                path.extend(spur_path);
+                if found.iter().chain(candidates.iter()).any(|(_, p)| *p == path) {
+                    continue;
+                }
+                if let Some(cost) = path_cost(&path, &movements) {
+                    candidates.push((cost, path));
+                }
+            }
+
+            let best = candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, (a, _)), (_, (b, _))| a.cmp(b))
+                .map(|(i, _)| i);
+            match best {
+                Some(i) => found.push(candidates.swap_remove(i)),
+                None => break,
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use astar::pathfinding::alt::Landmarks;
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::{Goal, Pathfinder};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+fn landmark_sets(grid: &Grid) -> [Landmarks<i32, Pos>; 2] {
+    [Landmarks::farthest((0, 0), 4, grid), Landmarks::avoid((0, 0), 4, grid)]
+}
+
+#[test]
+fn lower_bound_is_admissible() {
+    let grid = Grid::new();
+    for landmarks in landmark_sets(&grid) {
+        assert_eq!(landmarks.len(), 4);
+        for target in [(19, 19), (18, 2), (4, 10)] {
+            for pos in grid.free() {
+                let Some(cost) = astar_cost(&grid, pos, target) else { continue };
+                let bound = landmarks.lower_bound(&pos, &target);
+                assert!((0..=cost).contains(&bound), "{pos:?} -> {target:?}: {bound} > {cost}");
+                assert!(landmarks.goal(Octile(target), target).heuristic(&pos) <= cost);
+            }
+            assert_eq!(landmarks.lower_bound(&target, &target), 0);
+        }
+    }
+}
+
+#[test]
+fn astar_with_landmarks_matches_astar() {
+    let grid = Grid::new();
+    for landmarks in landmark_sets(&grid) {
+        let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+        for (start, goal) in common::queries() {
+            let path = astar.compute(start, landmarks.goal(Octile(goal), goal), &grid);
+            assert_eq!(path.map(|path| path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+        }
+    }
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use astar::pathfinding::wastar::{AStar, StaticWeight, Xup};
+use astar::pathfinding::PathResult;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+#[test]
+fn ends_optimal_with_decreasing_costs() {
+    let grid = Grid::new();
+    let mut wastar = AStar::<i32, Pos, _>::with_refpool_size(10000, StaticWeight::new(3.0));
+    for (start, goal) in common::queries() {
+        let optimal = astar_cost(&grid, start, goal);
+        let mut costs = vec![];
+        let result = wastar.compute_anytime(start, Octile(goal), &grid, usize::MAX, |cost, path| {
+            assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+            assert_eq!(path_cost(&grid, path), *cost);
+            costs.push(*cost);
+        });
+        match result {
+            PathResult::Complete(Some((cost, path))) => {
+                assert_eq!(Some(cost), optimal);
+                assert_eq!(path_cost(&grid, &path), cost);
+                assert_eq!(costs.last(), Some(&cost));
+                assert!(costs[0] as f64 <= 3.0 * cost as f64);
+                assert!(costs.windows(2).all(|w| w[1] < w[0]));
+            }
+            PathResult::NoPath(None) => assert_eq!(optimal, None),
+            _ => panic!("{start:?} -> {goal:?} didn't finish"),
+        }
+    }
+}
+
+#[test]
+fn timeout_keeps_the_incumbent() {
+    let grid = Grid::new();
+    let mut wastar = AStar::<i32, Pos, _>::with_refpool_size(10000, Xup::new(5.0));
+    let (start, goal) = ((0, 0), (19, 19));
+    let mut first = None;
+    let mut expansions = 0;
+    // greedy enough to find a path well before the open list runs dry
+    let result = loop {
+        expansions += 10;
+        match wastar.compute_anytime(start, Octile(goal), &grid, expansions, |cost, _| {
+            first.get_or_insert(*cost);
+        }) {
+            PathResult::Timeout(Some(incumbent)) => break incumbent,
+            PathResult::Timeout(None) => continue,
+            _ => panic!("finished before timing out with a path"),
+        }
+    };
+    let (cost, path) = result;
+    assert_eq!(path_cost(&grid, &path), cost);
+    assert!(cost >= astar_cost(&grid, start, goal).unwrap());
+    assert_eq!(first, Some(cost));
+}
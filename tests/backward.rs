@@ -0,0 +1,67 @@
+mod common;
+
+use astar::pathfinding::backward::AStar;
+use astar::pathfinding::{Movements, ReverseMovements};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+/// The grid where going up costs 5 more, so the direction matters
+struct Uphill(Grid);
+
+impl Movements<i32, Pos> for Uphill {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        let neighbors = self.0.get_neighbors(pos).into_iter();
+        neighbors.map(|(next, cost)| (next, cost + if next.1 > pos.1 { 5 } else { 0 })).collect()
+    }
+}
+
+impl ReverseMovements<i32, Pos> for Uphill {
+    fn get_predecessors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        let predecessors = self.0.get_neighbors(pos).into_iter();
+        predecessors.map(|(prev, cost)| (prev, cost + if pos.1 > prev.1 { 5 } else { 0 })).collect()
+    }
+}
+
+#[test]
+fn matches_astar() {
+    let world = Uphill(Grid::new());
+    let mut backward = AStar::new();
+    for (start, goal) in common::queries() {
+        let result = backward.compute(start, [goal], Octile(start), &world);
+        assert_eq!(result.as_ref().map(|(cost, _)| *cost), astar_cost(&world, start, goal));
+        if let Some((cost, path)) = result {
+            assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+            assert_eq!(path_cost(&world, &path), cost);
+        }
+    }
+}
+
+#[test]
+fn closest_of_several_goals() {
+    let world = Uphill(Grid::new());
+    let goals = [(19, 19), (18, 2), (4, 10)];
+    let mut backward = AStar::new();
+    for start in [(0, 0), (12, 9), (19, 0)] {
+        let (cost, path) = backward.compute(start, goals, Octile(start), &world).unwrap();
+        let closest = goals.iter().filter_map(|goal| astar_cost(&world, start, *goal)).min();
+        assert_eq!(Some(cost), closest);
+        assert!(goals.contains(path.last().unwrap()));
+    }
+}
+
+#[test]
+fn cost_to_goal_matches_astar() {
+    let world = Uphill(Grid::new());
+    let goals = [(19, 19), (18, 2)];
+    let mut backward = AStar::new();
+    for max_cost in [None, Some(150)] {
+        let costs = backward.cost_to_goal(goals, &world, max_cost);
+        for pos in world.0.free() {
+            let expected = goals
+                .iter()
+                .filter_map(|goal| astar_cost(&world, pos, *goal))
+                .min()
+                .filter(|cost| max_cost.is_none_or(|max| *cost <= max));
+            assert_eq!(costs.get(&pos).copied(), expected, "{pos:?}");
+        }
+    }
+}
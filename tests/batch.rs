@@ -0,0 +1,75 @@
+mod common;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::batch::Batch;
+use astar::pathfinding::Movements;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    let built = Arc::new(AtomicUsize::new(0));
+    let batch = {
+        let built = built.clone();
+        Batch::new(3, move || {
+            built.fetch_add(1, Ordering::Relaxed);
+            AStar::<i32, Pos>::with_refpool_size(10000)
+        })
+    };
+    assert_eq!(batch.threads(), 3);
+
+    let mut queries = common::queries();
+    queries.extend(grid.free().step_by(5).map(|start| (start, (19, 19))));
+    let goals: Arc<[_]> = queries.iter().map(|(start, goal)| (*start, Octile(*goal))).collect();
+    let grid = Arc::new(grid);
+    // the solvers are kept between calls
+    for _ in 0..3 {
+        let results = batch.compute(goals.clone(), grid.clone());
+        assert_eq!(results.len(), queries.len());
+        for ((start, goal), path) in queries.iter().zip(results) {
+            assert_eq!(path.map(|path| path_cost(&*grid, &path)), astar_cost(&*grid, *start, *goal));
+        }
+    }
+    assert_eq!(batch.compute::<Octile, _>(Arc::new([]), grid), vec![]);
+    assert_eq!(built.load(Ordering::Relaxed), 3);
+}
+
+/// Panics when a search expands the middle of the grid
+struct Trap(Grid);
+
+impl Movements<i32, Pos> for Trap {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        assert_ne!(pos, (10, 10), "trapped");
+        self.0.get_neighbors(pos)
+    }
+}
+
+#[test]
+fn survives_a_panic() {
+    let built = Arc::new(AtomicUsize::new(0));
+    let batch = {
+        let built = built.clone();
+        Batch::new(2, move || {
+            built.fetch_add(1, Ordering::Relaxed);
+            AStar::<i32, Pos>::with_refpool_size(10000)
+        })
+    };
+    let trap = Arc::new(Trap(Grid::new()));
+    let queries: Arc<[_]> = vec![((0, 0), Octile((19, 19))), ((0, 0), Octile((19, 0)))].into();
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| batch.compute(queries.clone(), trap.clone())));
+    assert_eq!(panicked.unwrap_err().downcast_ref::<String>().map(|message| message.contains("trapped")), Some(true));
+    // both queries go through the trap, and the workers go on with a new
+    // solver after each
+    assert_eq!(built.load(Ordering::Relaxed), 4);
+    assert_eq!(batch.threads(), 2);
+
+    let grid = Grid::new();
+    let results = batch.compute(queries.clone(), Arc::new(Grid::new()));
+    for ((start, goal), path) in queries.iter().zip(results) {
+        assert_eq!(path.map(|path| path_cost(&grid, &path)), astar_cost(&grid, *start, goal.0));
+    }
+}
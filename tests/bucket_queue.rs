@@ -0,0 +1,49 @@
+mod common;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::open_list::{BucketOrder, BucketQueue, OpenListError};
+use astar::pathfinding::wastar::StaticWeight;
+use astar::pathfinding::{astar_fat, gen_astar, wastar, Node, PathResult};
+use common::{astar_cost, path_cost, Grid, Octile, Pos, DIAGONAL};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    for order in [BucketOrder::Fifo, BucketOrder::Lifo] {
+        // f changes by at most two moves between open nodes
+        let mut astar = AStar::with_open_list(10000, BucketQueue::new(2 * DIAGONAL, order));
+        for (start, goal) in common::queries() {
+            let path = astar.try_compute(start, Octile(goal), &grid).unwrap();
+            assert_eq!(path.map(|path| path_cost(&grid, &path)), astar_cost(&grid, start, goal), "{order:?}");
+        }
+    }
+}
+
+/// Going around the walls raises f by more than one
+fn narrow<T>() -> BucketQueue<Pos, Node<i32, Pos, T>> {
+    BucketQueue::new(1, BucketOrder::Fifo)
+}
+
+#[test]
+fn out_of_span_is_an_error() {
+    let grid = Grid::new();
+    let (start, goal) = ((0, 0), (19, 19));
+
+    let mut astar = AStar::with_open_list(10000, narrow());
+    assert_eq!(astar.try_compute(start, Octile(goal), &grid), Err(OpenListError::OutOfSpan));
+    let mut astar = astar_fat::AStar::with_open_list(10000, narrow());
+    assert_eq!(astar.try_compute(start, Octile(goal), &grid), Err(OpenListError::OutOfSpan));
+    let mut wastar = wastar::AStar::with_open_list(10000, StaticWeight::new(1.0), narrow());
+    assert_eq!(wastar.try_compute(start, Octile(goal), &grid), Err(OpenListError::OutOfSpan));
+
+    let mut astar = gen_astar::AStar::with_open_list(10000, 0, start, Octile(goal), &grid, narrow());
+    assert!(matches!(astar.try_compute(), Err(OpenListError::OutOfSpan)));
+    assert!(matches!(astar.compute_closest(|_, g| *g), Err(OpenListError::OutOfSpan)));
+    // with a wide enough queue the path is found
+    let wide = BucketQueue::new(2 * DIAGONAL, BucketOrder::Fifo);
+    let mut astar = gen_astar::AStar::with_open_list(10000, 0, start, Octile(goal), &grid, wide);
+    let Ok(PathResult::Complete(path)) = astar.try_compute() else {
+        panic!("no path");
+    };
+    assert_eq!(Some(path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use astar::pathfinding::cbs::Cbs;
+use common::{astar_cost, Grid, Octile, Pos};
+
+fn at(path: &[(Pos, u32)], t: usize) -> Pos {
+    path.get(t).unwrap_or(path.last().unwrap()).0
+}
+
+#[test]
+fn single_agent_matches_astar() {
+    let grid = Grid::new();
+    for (start, goal) in common::queries() {
+        let result = Cbs::new(common::STRAIGHT, 100, 100).compute(&[(start, Octile(goal))], &grid);
+        assert_eq!(result.as_ref().map(|(cost, _)| *cost), astar_cost(&grid, start, goal));
+        if let Some((_, paths)) = result {
+            assert_eq!((paths[0][0].0, paths[0].last().unwrap().0), (start, goal));
+        }
+    }
+}
+
+#[test]
+fn crossing_agents_dont_collide() {
+    let grid = Grid::new();
+    let agents = [((0, 0), (4, 4)), ((4, 0), (0, 4)), ((0, 2), (4, 2)), ((2, 4), (2, 0))];
+    let goals: Vec<_> = agents.iter().map(|(start, goal)| (*start, Octile(*goal))).collect();
+    let (cost, paths) = Cbs::new(common::STRAIGHT, 100, 10000).compute(&goals, &grid).unwrap();
+
+    let alone: i32 = agents.iter().map(|(start, goal)| astar_cost(&grid, *start, *goal).unwrap()).sum();
+    assert!(cost >= alone);
+    for ((start, goal), path) in agents.iter().zip(&paths) {
+        assert_eq!((path[0].0, path.last().unwrap().0), (*start, *goal));
+    }
+    let end = paths.iter().map(|path| path.len()).max().unwrap();
+    for t in 0..end {
+        for a in 0..paths.len() {
+            for b in a + 1..paths.len() {
+                let (pa, pb) = (at(&paths[a], t), at(&paths[b], t));
+                assert_ne!(pa, pb, "agents {a} and {b} meet at {t}");
+                let (na, nb) = (at(&paths[a], t + 1), at(&paths[b], t + 1));
+                assert!(!(pa == nb && pb == na), "agents {a} and {b} swap at {t}");
+            }
+        }
+    }
+}
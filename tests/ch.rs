@@ -0,0 +1,49 @@
+mod common;
+
+use astar::pathfinding::ch::ContractionHierarchy;
+use common::{astar_cost, path_cost, Grid};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    let ch = ContractionHierarchy::build(grid.free(), &grid);
+    assert_eq!(ch.len(), grid.free().count());
+
+    let mut queries = common::queries();
+    for start in [(0, 0), (12, 9), (19, 19)] {
+        queries.extend(grid.free().step_by(7).map(|goal| (start, goal)));
+    }
+    for (start, goal) in queries {
+        let result = ch.compute(&start, &goal);
+        assert_eq!(result.as_ref().map(|(cost, _)| *cost), astar_cost(&grid, start, goal), "{start:?} -> {goal:?}");
+        if let Some((cost, path)) = result {
+            assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+            assert_eq!(path_cost(&grid, &path), cost);
+        }
+    }
+}
+
+#[test]
+fn positions_outside_the_graph() {
+    let grid = Grid::new();
+    let ch = ContractionHierarchy::build(grid.free().filter(|pos| pos.0 < 5), &grid);
+    assert!(ch.compute(&(0, 0), &(4, 19)).is_some());
+    assert_eq!(ch.compute(&(0, 0), &(19, 19)), None);
+    assert_eq!(ch.compute(&(5, 0), &(0, 0)), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let grid = Grid::new();
+    let ch = ContractionHierarchy::build(grid.free(), &grid);
+    let json = serde_json::to_string(&ch).unwrap();
+    // the index isn't stored, it's rebuilt from the positions
+    let loaded: ContractionHierarchy<i32, (i32, i32)> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.len(), ch.len());
+    for start in [(0, 0), (12, 9), (19, 19)] {
+        for goal in grid.free().step_by(5).chain([(14, 17), (30, 30)]) {
+            assert_eq!(loaded.compute(&start, &goal), ch.compute(&start, &goal), "{start:?} -> {goal:?}");
+        }
+    }
+}
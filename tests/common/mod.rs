@@ -0,0 +1,134 @@
+//! Small grid world shared by the solver tests
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::{Goal, Movements, Pathfinder, ReverseMovements};
+
+pub type Pos = (i32, i32);
+
+pub const STRAIGHT: i32 = 10;
+pub const DIAGONAL: i32 = 14;
+
+/// 8 connected grid with walls, moves cost 10 or 14
+pub struct Grid {
+    pub width: i32,
+    pub height: i32,
+    pub walls: HashSet<Pos>,
+}
+
+impl Grid {
+    /// 20x20 with a few walls and gaps, so paths have to go around
+    pub fn new() -> Self {
+        let mut walls = HashSet::new();
+        for y in 0..16 {
+            walls.insert((5, y));
+        }
+        for y in 4..20 {
+            walls.insert((11, y));
+        }
+        for x in 12..18 {
+            walls.insert((x, 8));
+        }
+        walls.insert((15, 14));
+        walls.insert((16, 13));
+        // (14, 17) can't be reached
+        for x in 13..=15 {
+            walls.insert((x, 16));
+            walls.insert((x, 18));
+        }
+        walls.insert((13, 17));
+        walls.insert((15, 17));
+        Self {
+            width: 20,
+            height: 20,
+            walls,
+        }
+    }
+
+    pub fn is_free(&self, pos: &Pos) -> bool {
+        pos.0 >= 0 && pos.1 >= 0 && pos.0 < self.width && pos.1 < self.height && !self.walls.contains(pos)
+    }
+
+    pub fn free(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x, y))).filter(|pos| self.is_free(pos))
+    }
+}
+
+impl Movements<i32, Pos> for Grid {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        if !self.is_free(&pos) {
+            return vec![];
+        }
+        let mut neighbors = vec![];
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let next = (pos.0 + dx, pos.1 + dy);
+                if (dx, dy) == (0, 0) || !self.is_free(&next) {
+                    continue;
+                }
+                neighbors.push((next, if dx != 0 && dy != 0 { DIAGONAL } else { STRAIGHT }));
+            }
+        }
+        neighbors
+    }
+}
+
+impl ReverseMovements<i32, Pos> for Grid {
+    fn get_predecessors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        self.get_neighbors(pos)
+    }
+}
+
+/// Exact without walls, so admissible and consistent
+#[derive(Clone, Copy)]
+pub struct Octile(pub Pos);
+
+impl Goal<i32, Pos> for Octile {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        *pos == self.0
+    }
+    fn heuristic(&self, pos: &Pos) -> i32 {
+        let (dx, dy) = ((pos.0 - self.0 .0).abs(), (pos.1 - self.0 .1).abs());
+        DIAGONAL * dx.min(dy) + STRAIGHT * (dx.max(dy) - dx.min(dy))
+    }
+}
+
+/// Start and goal pairs on both sides of the walls, plus one into the
+/// walled off (14, 17)
+pub fn queries() -> Vec<(Pos, Pos)> {
+    vec![
+        ((0, 0), (19, 19)),
+        ((2, 18), (18, 2)),
+        ((6, 0), (13, 19)),
+        ((19, 0), (0, 10)),
+        ((3, 3), (3, 3)),
+        ((0, 0), (14, 17)),
+    ]
+}
+
+/// Cost of walking path, panics if two consecutive positions aren't neighbors
+pub fn path_cost(movements: &impl Movements<i32, Pos>, path: &[Pos]) -> i32 {
+    path.windows(2)
+        .map(|w| {
+            movements
+                .get_neighbors(w[0])
+                .into_iter()
+                .filter(|(pos, _)| *pos == w[1])
+                .map(|(_, cost)| cost)
+                .min()
+                .unwrap_or_else(|| panic!("{:?} isn't next to {:?}", w[1], w[0]))
+        })
+        .sum()
+}
+
+/// Cost of the path plain A* finds, the reference the other solvers are
+/// checked against
+pub fn astar_cost(movements: &impl Movements<i32, Pos>, start: Pos, goal: Pos) -> Option<i32> {
+    let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+    let mut path = astar.compute(start, Octile(goal), movements)?;
+    // astar returns it from the goal back to the start
+    path.reverse();
+    Some(path_cost(movements, &path))
+}
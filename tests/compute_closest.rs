@@ -0,0 +1,51 @@
+mod common;
+
+use astar::pathfinding::gen_astar::AStar;
+use astar::pathfinding::{Goal, PathResult, PathfinderGen};
+use common::{astar_cost, path_cost, Grid, Octile};
+
+#[test]
+fn complete_when_reachable() {
+    let grid = Grid::new();
+    for (start, goal) in common::queries().into_iter().filter(|(_, goal)| *goal != (14, 17)) {
+        let mut astar: AStar<_, _, _, _> = AStar::new(10000, 0, start, Octile(goal), &grid);
+        let PathResult::Complete((path, d)) = astar.compute_closest(|pos, _| Octile(goal).heuristic(pos)).unwrap() else {
+            panic!("{start:?} -> {goal:?} not found");
+        };
+        assert_eq!(d, 0);
+        assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+        assert_eq!(Some(path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+    }
+}
+
+#[test]
+fn partial_to_the_closest_node() {
+    let grid = Grid::new();
+    let (start, goal) = ((0, 0), (14, 17));
+    let distance = |pos: &(i32, i32)| Octile(goal).heuristic(pos);
+    let closest = grid.free().filter(|pos| astar_cost(&grid, start, *pos).is_some()).map(|pos| distance(&pos)).min();
+
+    let mut astar: AStar<_, _, _, _> = AStar::new(10000, 0, start, Octile(goal), &grid);
+    // the state is reset after every call, so asking again gives the same
+    let mut cheapest = None;
+    for _ in 0..2 {
+        let PathResult::Partial((path, (d, g))) = astar.compute_closest(|pos, g| (distance(pos), *g)).unwrap() else {
+            panic!("expected a partial path");
+        };
+        assert_eq!(Some(d), closest);
+        assert_eq!(path[0], start);
+        assert_eq!(distance(path.last().unwrap()), d);
+        assert_eq!(path_cost(&grid, &path), g);
+        assert_eq!(Some(g), astar_cost(&grid, start, *path.last().unwrap()));
+        assert!(cheapest.is_none_or(|cheapest| cheapest == g));
+        cheapest = Some(g);
+    }
+
+    // without g in the metric any of the closest nodes will do
+    let PathResult::Partial((path, d)) = astar.compute_closest(|pos, _| distance(pos)).unwrap() else {
+        panic!("expected a partial path");
+    };
+    assert_eq!(Some(d), closest);
+    assert_eq!(distance(path.last().unwrap()), d);
+    assert!(path_cost(&grid, &path) >= cheapest.unwrap());
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::open_list::{BucketOrder, BucketQueue, LazyHeap, OpenListError};
+use astar::pathfinding::{gen_astar, PathResult, Pathfinder, PathfinderGen};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+#[test]
+fn cheapest_start_wins() {
+    let grid = Grid::new();
+    let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+    let starts = [((0, 0), 0), ((19, 0), 30), ((8, 19), 0), ((0, 19), 200)];
+    for goal in [(19, 19), (18, 2), (4, 10), (12, 5)] {
+        let (index, path) = astar.compute_multi(starts, Octile(goal), &grid).unwrap();
+        // like compute, the path goes from the goal back to the start
+        assert_eq!((path[0], *path.last().unwrap()), (goal, starts[index].0));
+        let cost = starts[index].1 + path_cost(&grid, &path);
+        let cheapest = starts.iter().filter_map(|(start, g)| Some(g + astar_cost(&grid, *start, goal)?)).min();
+        assert_eq!(Some(cost), cheapest, "{goal:?}");
+    }
+    assert_eq!(astar.compute_multi(starts, Octile((14, 17)), &grid), None);
+    assert_eq!(astar.compute_multi([], Octile((19, 19)), &grid), None);
+}
+
+#[test]
+fn duplicated_starts_use_the_cheapest() {
+    let grid = Grid::new();
+    let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+    let starts = [((0, 0), 50), ((12, 9), 40), ((0, 0), 10)];
+    let (index, path) = astar.compute_multi(starts, Octile((4, 10)), &grid).unwrap();
+    assert_eq!(index, 2);
+    assert_eq!(path.last(), Some(&(0, 0)));
+}
+
+#[test]
+fn single_start_is_compute() {
+    let grid = Grid::new();
+    let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+    for (start, goal) in common::queries() {
+        let multi = astar.compute_multi([(start, 0)], Octile(goal), &grid);
+        let path = astar.compute(start, Octile(goal), &grid);
+        assert_eq!(multi.map(|(_, path)| path), path);
+    }
+    // already at the goal
+    assert_eq!(astar.compute_multi([((3, 3), 0)], Octile((3, 3)), &grid), Some((0, vec![(3, 3)])));
+}
+
+#[test]
+fn gen_astar_from_several_starts() {
+    let grid = Grid::new();
+    let starts = [((0, 0), 0), ((19, 0), 30), ((8, 19), 0), ((0, 19), 200)];
+    for goal in [(19, 19), (18, 2), (4, 10), (12, 5)] {
+        let mut astar = gen_astar::AStar::with_starts(10000, 0, starts, Octile(goal), &grid, LazyHeap::new()).unwrap();
+        // the search is reset after a complete path, so it finds the same again
+        for _ in 0..2 {
+            let PathResult::Complete(path) = astar.compute() else {
+                panic!("{goal:?} not found");
+            };
+            // gen_astar paths begin at the start they came from
+            let (start, g) = starts.iter().filter(|(start, _)| *start == path[0]).min_by_key(|(_, g)| *g).unwrap();
+            assert_eq!(*path.last().unwrap(), goal);
+            let cheapest = starts.iter().filter_map(|(start, g)| Some(g + astar_cost(&grid, *start, goal)?)).min();
+            assert_eq!(Some(g + path_cost(&grid, &path)), cheapest, "{start:?} -> {goal:?}");
+        }
+    }
+}
+
+#[test]
+fn gen_astar_starts_out_of_span() {
+    let grid = Grid::new();
+    let starts = [((0, 0), 0), ((8, 19), 500)];
+    let narrow = BucketQueue::new(2 * common::DIAGONAL, BucketOrder::Fifo);
+    let astar = gen_astar::AStar::with_starts(10000, 0, starts, Octile((19, 19)), &grid, narrow);
+    assert!(matches!(astar, Err(OpenListError::OutOfSpan)));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use astar::pathfinding::dense::AStar;
+use astar::pathfinding::Pathfinder;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    // the storage is reused between searches
+    let mut dense = AStar::<i32, Pos>::new(((0, 0), (grid.width, grid.height)));
+    let mut queries = common::queries();
+    queries.extend(grid.free().step_by(7).map(|start| (start, (19, 0))));
+    for (start, goal) in queries {
+        let path = dense.compute(start, Octile(goal), &grid);
+        if let Some(path) = &path {
+            assert_eq!((path[0], *path.last().unwrap()), (goal, start));
+        }
+        assert_eq!(path.map(|path| path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+    }
+}
+
+#[test]
+fn out_of_bounds_is_a_wall() {
+    let grid = Grid::new();
+    // the left half only, where the wall at x = 5 ends at y = 15
+    let mut dense = AStar::<i32, Pos>::new(((0, 0), (10, 20)));
+    let path = dense.compute((0, 0), Octile((9, 0)), &grid).unwrap();
+    assert!(path.iter().all(|pos| pos.0 < 10));
+    assert_eq!(Some(path_cost(&grid, &path)), astar_cost(&grid, (0, 0), (9, 0)));
+    assert_eq!(dense.compute((0, 0), Octile((19, 19)), &grid), None);
+}
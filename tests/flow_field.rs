@@ -0,0 +1,53 @@
+mod common;
+
+use astar::pathfinding::flow_field::FlowField;
+use common::{astar_cost, path_cost, Grid, Pos};
+
+const GOALS: [Pos; 2] = [(19, 19), (18, 2)];
+
+/// Checks every cost and path of the field against A* to the closest goal
+fn check(field: &mut FlowField<i32, Pos, Grid>, max_cost: i32) {
+    let grid = Grid {
+        walls: field.movements_mut().walls.clone(),
+        ..Grid::new()
+    };
+    for pos in grid.free() {
+        let expected = GOALS
+            .iter()
+            .filter_map(|goal| astar_cost(&grid, pos, *goal))
+            .min()
+            .filter(|cost| *cost <= max_cost);
+        assert_eq!(field.cost(&pos).copied(), expected, "{pos:?}");
+        let path = field.path(pos);
+        assert_eq!(path.as_ref().map(|path| path_cost(&grid, path)), expected);
+        if let Some(path) = path {
+            assert_eq!(path[0], pos);
+            assert!(GOALS.contains(path.last().unwrap()));
+            assert_eq!(field.next_step(&pos), path.get(1).copied());
+        }
+    }
+}
+
+#[test]
+fn matches_astar() {
+    check(&mut FlowField::new(GOALS, Grid::new(), None), i32::MAX);
+}
+
+#[test]
+fn max_cost_leaves_positions_out() {
+    check(&mut FlowField::new(GOALS, Grid::new(), Some(100)), 100);
+}
+
+#[test]
+fn update_matches_a_new_field() {
+    let mut field = FlowField::new(GOALS, Grid::new(), None);
+    // close the gap under the second wall and open one in the first
+    let (added, removed) = ([(11, 3), (11, 2)], [(5, 8), (5, 9)]);
+    let grid = field.movements_mut();
+    grid.walls.extend(added);
+    for pos in &removed {
+        grid.walls.remove(pos);
+    }
+    field.update(added.into_iter().chain(removed));
+    check(&mut field, i32::MAX);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use astar::pathfinding::gen_astar::{AStar, AStarNode};
+use astar::pathfinding::open_list::{DaryHeap, LazyHeap, OpenList, PairingHeap, RadixHeap};
+use astar::pathfinding::{PathResult, PathfinderGen};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+/// Runs the queries through PathfinderGen with the open list O
+fn matches_astar<O: OpenList<Pos, AStarNode<i32, Pos>> + Default>() {
+    let grid = Grid::new();
+    for (start, goal) in common::queries() {
+        let mut astar = AStar::<_, _, _, _, O>::new(10000, 0, start, Octile(goal), &grid);
+        // reset starts over, so the second search finds the same
+        for _ in 0..2 {
+            let cost = match astar.compute() {
+                PathResult::Complete(path) => Some(path_cost(&grid, &path)),
+                _ => None,
+            };
+            assert_eq!(cost, astar_cost(&grid, start, goal));
+            astar.reset();
+        }
+    }
+}
+
+#[test]
+fn every_open_list() {
+    matches_astar::<LazyHeap<_, _>>();
+    matches_astar::<DaryHeap<_, _>>();
+    matches_astar::<PairingHeap<_, _>>();
+    matches_astar::<RadixHeap<_, _>>();
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use astar::pathfinding::hda::Hda;
+use common::{astar_cost, path_cost, Grid, Octile};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    for threads in [1, 2, 4] {
+        for (start, goal) in common::queries() {
+            let result = Hda::new(threads).compute(start, Octile(goal), &grid);
+            assert_eq!(result.as_ref().map(|(cost, _)| *cost), astar_cost(&grid, start, goal), "{threads} threads");
+            if let Some((cost, path)) = result {
+                assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+                assert_eq!(path_cost(&grid, &path), cost);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use astar::pathfinding::hpa::{Clustering, Hpa};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+/// 5x5 chunks of the 20x20 grid, walls included as they have no moves
+struct Chunks;
+
+impl Clustering<Pos> for Chunks {
+    type Cluster = Pos;
+
+    fn cluster(&self, pos: &Pos) -> Pos {
+        (pos.0.div_euclid(5), pos.1.div_euclid(5))
+    }
+    fn positions(&self, cluster: &Pos) -> Vec<Pos> {
+        (0..5).flat_map(|y| (0..5).map(move |x| (cluster.0 * 5 + x, cluster.1 * 5 + y))).collect()
+    }
+}
+
+fn check(hpa: &mut Hpa<i32, Pos, impl Clustering<Pos, Cluster = Pos>, Grid>, start: Pos, goal: Pos) {
+    let path = hpa.compute(start, goal, Octile(goal));
+    let grid = hpa.movements_mut();
+    let expected = astar_cost(grid, start, goal);
+    assert_eq!(path.as_ref().map(|path| path_cost(grid, path)), expected, "{start:?} -> {goal:?}");
+    if let Some(path) = path {
+        assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+    }
+}
+
+#[test]
+fn matches_astar() {
+    let mut hpa = Hpa::new(Chunks, Grid::new());
+    for (start, goal) in common::queries() {
+        check(&mut hpa, start, goal);
+    }
+    for (start, goal) in [((4, 0), (6, 0)), ((12, 9), (19, 0)), ((0, 19), (19, 19))] {
+        check(&mut hpa, start, goal);
+    }
+}
+
+#[test]
+fn invalidate_picks_up_changes() {
+    let mut hpa = Hpa::new(Chunks, Grid::new());
+    check(&mut hpa, (0, 0), (9, 0));
+    // open a gap in the first wall
+    for pos in [(5, 1), (5, 2)] {
+        hpa.movements_mut().walls.remove(&pos);
+        hpa.invalidate(&pos);
+    }
+    check(&mut hpa, (0, 0), (9, 0));
+}
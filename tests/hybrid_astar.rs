@@ -0,0 +1,95 @@
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use astar::pathfinding::hybrid_astar::{dubins, HybridAStar, Pose, PoseGoal, Vehicle};
+use astar::pathfinding::Goal;
+
+const RADIUS: f64 = 2.0;
+
+/// 30x20 field with a pillar in the middle
+fn field(pose: &Pose) -> bool {
+    (0.0..30.0).contains(&pose.x) && (0.0..20.0).contains(&pose.y) && Pose::new(15.0, 10.0, 0.0).distance(pose) > 4.0
+}
+
+fn turn(a: f64, b: f64) -> f64 {
+    let turn = (b - a).rem_euclid(TAU);
+    turn.min(TAU - turn)
+}
+
+/// Every pose is free, close to the previous one and the heading never
+/// turns tighter than the radius allows
+fn check(start: Pose, target: &PoseGoal, path: &[Pose], step: f64) {
+    assert_eq!(path[0], start);
+    assert!(target.is_reached(path.last().unwrap()));
+    for pose in path {
+        assert!(field(pose), "{pose:?} collides");
+    }
+    for w in path.windows(2) {
+        let distance = w[0].distance(&w[1]);
+        assert!(distance <= step + 1e-9, "{:?} -> {:?}", w[0], w[1]);
+        // chords are a little shorter than the arcs they cut
+        assert!(turn(w[0].heading, w[1].heading) <= 1.01 * distance / RADIUS + 1e-9);
+    }
+}
+
+#[test]
+fn drives_around_the_pillar() {
+    let vehicle = Vehicle::new(RADIUS, 1.0);
+    let (start, goal) = (Pose::new(2.0, 10.0, 0.0), Pose::new(28.0, 10.0, PI));
+    let target = PoseGoal {
+        pose: goal,
+        tolerance: 0.5,
+        heading_tolerance: 0.1,
+    };
+    let (cost, path) = HybridAStar::new(vehicle).compute(start, goal, target, field).unwrap();
+    check(start, &target, &path, vehicle.step);
+    let length: f64 = path.windows(2).map(|w| w[0].distance(&w[1])).sum();
+    assert!(cost.0 >= length - 1e-6);
+    assert!(cost.0 > start.distance(&goal));
+}
+
+#[test]
+fn reverses_into_a_spot() {
+    let vehicle = Vehicle {
+        reverse: Some(2.0),
+        switch_penalty: 1.0,
+        ..Vehicle::new(RADIUS, 1.0)
+    };
+    let (start, goal) = (Pose::new(3.0, 3.0, 0.0), Pose::new(3.0, 6.0, FRAC_PI_2));
+    let target = PoseGoal {
+        pose: goal,
+        tolerance: 0.5,
+        heading_tolerance: 0.2,
+    };
+    let (_, path) = HybridAStar::new(vehicle).compute(start, goal, target, field).unwrap();
+    check(start, &target, &path, vehicle.step);
+}
+
+#[test]
+fn unreachable_goal() {
+    let vehicle = Vehicle {
+        max_expansions: 5000,
+        ..Vehicle::new(RADIUS, 1.0)
+    };
+    let goal = Pose::new(15.0, 10.0, 0.0);
+    let target = PoseGoal {
+        pose: goal,
+        tolerance: 0.5,
+        heading_tolerance: 0.1,
+    };
+    assert!(HybridAStar::new(vehicle).compute(Pose::new(2.0, 10.0, 0.0), goal, target, field).is_none());
+}
+
+#[test]
+fn dubins_lengths() {
+    let straight = dubins(&Pose::new(0.0, 0.0, 0.0), &Pose::new(10.0, 0.0, 0.0), RADIUS).unwrap();
+    assert!((straight.iter().map(|(_, length)| length).sum::<f64>() - 10.0).abs() < 1e-9);
+
+    // a half circle to the left
+    let u_turn = dubins(&Pose::new(0.0, 0.0, 0.0), &Pose::new(0.0, 2.0 * RADIUS, PI), RADIUS).unwrap();
+    assert!((u_turn.iter().map(|(_, length)| length).sum::<f64>() - PI * RADIUS).abs() < 1e-6);
+
+    // driving the segments ends at the goal
+    let (from, to) = (Pose::new(1.0, 2.0, 0.3), Pose::new(-4.0, 7.0, 2.5));
+    let end = dubins(&from, &to, RADIUS).unwrap().iter().fold(from, |pose, (curvature, length)| pose.drive(*curvature, *length));
+    assert!(end.distance(&to) < 1e-6 && turn(end.heading, to.heading) < 1e-6);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use astar::pathfinding::namoa::{AStar, CostVec, Dominance};
+use astar::pathfinding::{Goal, Movements};
+use common::{astar_cost, path_cost, queries, Grid, Octile, Pos};
+
+type Cost = CostVec<i32, 2>;
+
+/// Moves cost [distance, 1 for every step onto the left half]
+struct Risky<'a>(&'a Grid);
+
+impl Movements<Cost, Pos> for Risky<'_> {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, Cost)> {
+        self.0
+            .get_neighbors(pos)
+            .into_iter()
+            .map(|(next, cost)| (next, CostVec([cost, (next.0 < 10) as i32])))
+            .collect()
+    }
+}
+
+/// Only the risk of the Risky moves
+struct Risk<'a>(&'a Grid);
+
+impl Movements<i32, Pos> for Risk<'_> {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        self.0.get_neighbors(pos).into_iter().map(|(next, _)| (next, (next.0 < 10) as i32)).collect()
+    }
+}
+
+struct Both(Octile);
+
+impl Goal<Cost, Pos> for Both {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        self.0.is_reached(pos)
+    }
+    fn heuristic(&self, pos: &Pos) -> Cost {
+        CostVec([self.0.heuristic(pos), 0])
+    }
+}
+
+#[test]
+fn front_has_the_cheapest_path_of_each_objective() {
+    let grid = Grid::new();
+    let mut namoa = AStar::new();
+    for (start, goal) in queries() {
+        let front = namoa.compute(start, Both(Octile(goal)), Risky(&grid));
+        let Some(distance) = astar_cost(&grid, start, goal) else {
+            assert!(front.is_empty());
+            continue;
+        };
+        assert!(!front.is_empty());
+
+        for (cost, path) in &front {
+            assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+            assert_eq!(cost.0, [path_cost(&grid, path), path_cost(&Risk(&grid), path)]);
+        }
+        for (i, (a, _)) in front.iter().enumerate() {
+            for (b, _) in &front[i + 1..] {
+                assert!(!a.dominates(b) && !b.dominates(a), "{a:?} and {b:?} on one front");
+            }
+        }
+        assert_eq!(front.iter().map(|(cost, _)| cost.0[0]).min(), Some(distance));
+
+        let mut zero = astar::pathfinding::astar::AStar::<i32, Pos>::with_refpool_size(10000);
+        let mut safest = astar::pathfinding::Pathfinder::compute(&mut zero, start, Zero(goal), Risk(&grid)).unwrap();
+        safest.reverse();
+        assert_eq!(front.iter().map(|(cost, _)| cost.0[1]).min(), Some(path_cost(&Risk(&grid), &safest)));
+    }
+}
+
+struct Zero(Pos);
+
+impl Goal<i32, Pos> for Zero {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        *pos == self.0
+    }
+    fn heuristic(&self, _: &Pos) -> i32 {
+        0
+    }
+}
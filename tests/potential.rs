@@ -0,0 +1,83 @@
+mod common;
+
+use astar::pathfinding::potential::AStar;
+use astar::pathfinding::{Goal, Movements, PathResult, PathfinderGen};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+use fixed::types::I22F10;
+
+/// The grid with its costs mapped into another cost type
+struct Scaled<F> {
+    grid: Grid,
+    scale: fn(i32) -> F,
+}
+
+impl<F> Movements<F, Pos> for Scaled<F> {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, F)> {
+        let neighbors = self.grid.get_neighbors(pos).into_iter();
+        neighbors.map(|(next, cost)| (next, (self.scale)(cost))).collect()
+    }
+}
+
+struct ScaledOctile<F>(Pos, fn(i32) -> F);
+
+impl<F> Goal<F, Pos> for ScaledOctile<F> {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        *pos == self.0
+    }
+    fn heuristic(&self, pos: &Pos) -> F {
+        (self.1)(Octile(self.0).heuristic(pos))
+    }
+}
+
+#[test]
+fn within_max_cost() {
+    let grid = Grid::new();
+    for (start, goal) in common::queries() {
+        let optimal = astar_cost(&grid, start, goal);
+        for factor in [1.0, 1.2, 2.0] {
+            let max_cost = (factor * optimal.unwrap_or(100) as f64) as i32;
+            let mut potential = AStar::new(1000, max_cost.max(1), start, Octile(goal), &grid);
+            match potential.compute() {
+                PathResult::Complete(path) => {
+                    assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+                    assert!(path_cost(&grid, &path) <= max_cost.max(1));
+                }
+                PathResult::NoPath(_) => assert_eq!(optimal, None),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+#[test]
+fn nothing_under_the_optimal_cost() {
+    let grid = Grid::new();
+    let optimal = astar_cost(&grid, (0, 0), (19, 19)).unwrap();
+    let mut potential = AStar::new(1000, optimal - 1, (0, 0), Octile((19, 19)), &grid);
+    assert!(matches!(potential.compute(), PathResult::NoPath(_)));
+}
+
+/// h * slack would overflow the cost type in both of these
+#[test]
+fn large_costs_dont_overflow() {
+    let (start, goal) = ((0, 0), (19, 19));
+    let optimal = astar_cost(&Grid::new(), start, goal).unwrap();
+
+    let world = Scaled::<i32> {
+        grid: Grid::new(),
+        scale: |cost| cost * 1_000_000,
+    };
+    let max_cost = optimal * 1_500_000;
+    let mut potential = AStar::new(1000, max_cost, start, ScaledOctile(goal, world.scale), &world);
+    let PathResult::Complete(path) = potential.compute() else { panic!("no path") };
+    assert!(path_cost(&world.grid, &path) * 1_000_000 <= max_cost);
+
+    let world = Scaled::<I22F10> {
+        grid: Grid::new(),
+        scale: |cost| I22F10::from_num(cost * 10),
+    };
+    let max_cost = I22F10::from_num(optimal * 15);
+    let mut potential = AStar::new(1000, max_cost, start, ScaledOctile(goal, world.scale), &world);
+    let PathResult::Complete(path) = potential.compute() else { panic!("no path") };
+    assert!(I22F10::from_num(path_cost(&world.grid, &path) * 10) <= max_cost);
+}
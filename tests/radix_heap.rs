@@ -0,0 +1,18 @@
+mod common;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::open_list::RadixHeap;
+use astar::pathfinding::Pathfinder;
+use common::{astar_cost, path_cost, Grid, Octile};
+
+#[test]
+fn matches_astar() {
+    let grid = Grid::new();
+    let mut astar = AStar::with_open_list(10000, RadixHeap::new());
+    let mut queries = common::queries();
+    queries.extend(grid.free().step_by(7).map(|start| (start, (19, 0))));
+    for (start, goal) in queries {
+        let path = astar.compute(start, Octile(goal), &grid);
+        assert_eq!(path.map(|path| path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+    }
+}
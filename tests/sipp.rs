@@ -0,0 +1,71 @@
+mod common;
+
+use astar::pathfinding::sipp::{AStar, SafeIntervals};
+use astar::pathfinding::{Goal, Movements};
+use common::{astar_cost, Grid, Octile, Pos};
+
+/// The grid with move costs as durations in ticks
+struct Ticks<'a>(&'a Grid);
+
+impl Movements<u32, Pos> for Ticks<'_> {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, u32)> {
+        self.0.get_neighbors(pos).into_iter().map(|(next, cost)| (next, cost as u32)).collect()
+    }
+}
+
+struct Target(Pos);
+
+impl Goal<u32, Pos> for Target {
+    fn is_reached(&self, pos: &Pos) -> bool {
+        Octile(self.0).is_reached(pos)
+    }
+    fn heuristic(&self, pos: &Pos) -> u32 {
+        Octile(self.0).heuristic(pos) as u32
+    }
+}
+
+fn is_safe(intervals: &SafeIntervals<Pos>, pos: &Pos, t: u32) -> bool {
+    intervals.intervals(pos).iter().any(|(from, to)| (*from..=*to).contains(&t))
+}
+
+#[test]
+fn no_collisions_matches_astar() {
+    let grid = Grid::new();
+    let intervals = SafeIntervals::new();
+    for (start, goal) in common::queries() {
+        let path = AStar::new().compute(start, 7, Target(goal), Ticks(&grid), &intervals);
+        let arrival = path.as_ref().map(|path| path.last().unwrap().1 as i32 - 7);
+        assert_eq!(arrival, astar_cost(&grid, start, goal));
+        if let Some(path) = path {
+            assert_eq!(path[0], (start, 7));
+            assert_eq!(path.last().unwrap().0, goal);
+        }
+    }
+}
+
+#[test]
+fn waits_for_obstacles() {
+    let grid = Grid::new();
+    let (start, goal) = ((0, 0), (19, 19));
+    let free = AStar::new().compute(start, 0, Target(goal), Ticks(&grid), &SafeIntervals::new()).unwrap();
+
+    // an obstacle sits on the third step of the path for a while
+    let mut intervals = SafeIntervals::new();
+    let (blocked, t) = free[3];
+    intervals.add_collision(blocked, t.saturating_sub(20), t + 40);
+    assert!(!is_safe(&intervals, &blocked, t));
+
+    let path = AStar::new().compute(start, 0, Target(goal), Ticks(&grid), &intervals).unwrap();
+    assert!(path.last().unwrap().1 >= free.last().unwrap().1);
+    assert_eq!(path.last().unwrap().0, goal);
+    for (pos, t) in &path {
+        assert!(is_safe(&intervals, pos, *t), "{pos:?} unsafe at {t}");
+    }
+    let cost = |a: Pos, b: Pos| Ticks(&grid).get_neighbors(a).into_iter().find(|(n, _)| *n == b).unwrap().1;
+    for w in path.windows(2) {
+        // the agent waits in the safe interval it arrived in until it leaves
+        let leave = w[1].1 - cost(w[0].0, w[1].0);
+        assert!(leave >= w[0].1);
+        assert!(is_safe(&intervals, &w[0].0, leave));
+    }
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use astar::pathfinding::space_time::{AStar, ReservationTable};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+fn solver() -> AStar<i32, Pos> {
+    AStar::new(common::STRAIGHT, 200)
+}
+
+fn positions(path: &[(Pos, u32)]) -> Vec<Pos> {
+    path.iter().map(|(pos, _)| *pos).collect()
+}
+
+#[test]
+fn empty_table_matches_astar() {
+    let grid = Grid::new();
+    let reservations = ReservationTable::new();
+    for (start, goal) in common::queries() {
+        let result = solver().compute(start, 3, Octile(goal), &grid, &reservations);
+        assert_eq!(result.as_ref().map(|(cost, _)| *cost), astar_cost(&grid, start, goal));
+        if let Some((cost, path)) = result {
+            assert_eq!(path[0], (start, 3));
+            assert_eq!(path.last().unwrap().0, goal);
+            assert!(path.windows(2).all(|w| w[1].1 == w[0].1 + 1));
+            assert_eq!(path_cost(&grid, &positions(&path)), cost);
+        }
+    }
+}
+
+#[test]
+fn avoids_reservations() {
+    let grid = Grid::new();
+    let (start, goal) = ((0, 0), (19, 19));
+    let mut reservations = ReservationTable::new();
+    let (free_cost, free_path) = solver().compute(start, 0, Octile(goal), &grid, &reservations).unwrap();
+
+    // another agent stands on the path while this one would pass, then
+    // comes back along it
+    for (pos, t) in &free_path[3..6] {
+        reservations.reserve(*pos, *t);
+    }
+    let back: Vec<_> = free_path[8..12].iter().rev().zip(12..).map(|((pos, _), t)| (*pos, t)).collect();
+    reservations.reserve_path(&back);
+
+    let (cost, path) = solver().compute(start, 0, Octile(goal), &grid, &reservations).unwrap();
+    assert!(cost >= free_cost);
+    assert_ne!(path, free_path);
+    for (pos, t) in &path {
+        assert!(reservations.is_free(pos, *t), "{pos:?} taken at {t}");
+    }
+    for w in path.windows(2) {
+        assert!(reservations.is_edge_free(&w[0].0, &w[1].0, w[0].1));
+    }
+    assert_eq!(path.last().unwrap().0, goal);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::cell::Cell;
+use std::collections::HashSet;
+
+use astar::pathfinding::astar::{AStar, AStarNode};
+use astar::pathfinding::open_list::{BucketOrder, BucketQueue, DaryHeap, LazyHeap, OpenList, PairingHeap, RadixHeap};
+use astar::pathfinding::{Movements, Pathfinder, TieBreak};
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+/// Counts the nodes a search expands
+struct Counted<'a> {
+    grid: &'a Grid,
+    expanded: Cell<usize>,
+}
+
+impl Movements<i32, Pos> for Counted<'_> {
+    fn get_neighbors(&self, pos: Pos) -> Vec<(Pos, i32)> {
+        self.expanded.set(self.expanded.get() + 1);
+        self.grid.get_neighbors(pos)
+    }
+}
+
+/// Nodes expanded going across an open 30x30 grid, where most of the
+/// nodes tie on f
+fn expansions<O: OpenList<Pos, AStarNode<i32, Pos>>>(open: O, tie_break: TieBreak) -> usize {
+    let grid = Grid {
+        width: 30,
+        height: 30,
+        walls: HashSet::new(),
+    };
+    let counted = Counted {
+        grid: &grid,
+        expanded: Cell::new(0),
+    };
+    let mut astar = AStar::with_open_list(10000, open);
+    astar.set_tie_break(tie_break);
+    let path = astar.compute((0, 0), Octile((29, 17)), &counted).unwrap();
+    assert_eq!(path_cost(&grid, &path), astar_cost(&grid, (0, 0), (29, 17)).unwrap());
+    counted.expanded.get()
+}
+
+/// Expansions with every open list that follows the tie break of the
+/// solver
+fn every_open_list(tie_break: TieBreak) -> [usize; 5] {
+    [
+        expansions(LazyHeap::new(), tie_break),
+        expansions(DaryHeap::<_, _>::new(), tie_break),
+        expansions(DaryHeap::<_, _, 2>::new(), tie_break),
+        expansions(PairingHeap::new(), tie_break),
+        expansions(RadixHeap::new(), tie_break),
+    ]
+}
+
+#[test]
+fn every_tie_break_is_optimal() {
+    let grid = Grid::new();
+    let tie_breaks = [
+        TieBreak::HigherG,
+        TieBreak::LowerG,
+        TieBreak::LowerH,
+        TieBreak::Fifo,
+        TieBreak::Lifo,
+        TieBreak::Random(0),
+        TieBreak::Random(12345),
+    ];
+    for tie_break in tie_breaks {
+        let mut astar = AStar::<i32, Pos>::with_refpool_size(10000);
+        astar.set_tie_break(tie_break);
+        assert_eq!(astar.tie_break(), tie_break);
+        let mut queries = common::queries();
+        queries.extend(grid.free().step_by(11).map(|start| (start, (18, 2))));
+        for (start, goal) in queries {
+            let path = astar.compute(start, Octile(goal), &grid);
+            if let Some(path) = &path {
+                assert_eq!((path[0], *path.last().unwrap()), (goal, start));
+            }
+            let cost = path.map(|path| path_cost(&grid, &path));
+            assert_eq!(cost, astar_cost(&grid, start, goal), "{tie_break:?}");
+        }
+    }
+}
+
+#[test]
+fn every_open_list_keeps_the_tie_break() {
+    // these order every tie, so all the open lists pop the same nodes
+    for tie_break in [TieBreak::Fifo, TieBreak::Lifo, TieBreak::Random(7)] {
+        let counts = every_open_list(tie_break);
+        assert!(counts.iter().all(|count| *count == counts[0]), "{tie_break:?} {counts:?}");
+    }
+    let higher = every_open_list(TieBreak::HigherG);
+    let lower = every_open_list(TieBreak::LowerG);
+    for (higher, lower) in higher.into_iter().zip(lower) {
+        assert_ne!(higher, lower);
+    }
+}
+
+#[test]
+fn deeper_first_expands_fewer() {
+    let higher = every_open_list(TieBreak::HigherG);
+    let lower = every_open_list(TieBreak::LowerG);
+    let fifo = every_open_list(TieBreak::Fifo);
+    let lifo = every_open_list(TieBreak::Lifo);
+    for i in 0..higher.len() {
+        assert!(higher[i] < lower[i], "{higher:?} {lower:?}");
+        assert!(lifo[i] < fifo[i], "{lifo:?} {fifo:?}");
+    }
+    // h = f - g, so on a tie the lower h is the higher g
+    assert_eq!(every_open_list(TieBreak::LowerH), higher);
+
+    // the bucket queue has its own order instead
+    let bucket_queue = |order| expansions(BucketQueue::new(2 * common::DIAGONAL, order), TieBreak::default());
+    assert!(bucket_queue(BucketOrder::Lifo) < bucket_queue(BucketOrder::Fifo));
+}
+
+#[test]
+fn default_is_higher_g() {
+    assert_eq!(TieBreak::default(), TieBreak::HigherG);
+    assert_eq!(AStar::<i32, Pos>::with_refpool_size(16).tie_break(), TieBreak::HigherG);
+}
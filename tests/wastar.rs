@@ -0,0 +1,90 @@
+mod common;
+
+use astar::pathfinding::wastar::{AStar, DynamicWeight, Priority, PwXd, PwXu, StaticWeight, Xdp, Xup};
+use astar::pathfinding::Pathfinder;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+/// Any of the policies, so one solver type runs them all
+struct Policy(Box<dyn Priority<i32>>);
+
+impl Priority<i32> for Policy {
+    fn priority(&self, g: i32, h: i32, depth: u32) -> i32 {
+        self.0.priority(g, h, depth)
+    }
+    fn bound(&self) -> f64 {
+        self.0.bound()
+    }
+}
+
+fn policies(w: f64) -> Vec<Policy> {
+    vec![
+        Policy(Box::new(StaticWeight::new(w))),
+        Policy(Box::new(Xdp::new(w))),
+        Policy(Box::new(Xup::new(w))),
+        Policy(Box::new(PwXu::new(w))),
+        Policy(Box::new(PwXd::new(w))),
+        Policy(Box::new(DynamicWeight::new(w - 1.0, 20))),
+    ]
+}
+
+#[test]
+fn within_bound() {
+    let grid = Grid::new();
+    for w in [1.0, 1.25, 1.5, 2.0, 5.0] {
+        for policy in policies(w) {
+            assert_eq!(policy.bound(), w);
+            let mut wastar = AStar::<i32, Pos, _>::with_refpool_size(10000, policy);
+            for (start, goal) in common::queries() {
+                let optimal = astar_cost(&grid, start, goal);
+                let path = wastar.compute(start, Octile(goal), &grid);
+                assert_eq!(path.is_some(), optimal.is_some());
+                let (Some(mut path), Some(optimal)) = (path, optimal) else { continue };
+                path.reverse();
+                assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+                let cost = path_cost(&grid, &path);
+                assert!(cost as f64 <= w * optimal as f64, "w {w}: {cost} > {optimal}");
+                if w == 1.0 {
+                    assert_eq!(cost, optimal);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn goals_come_out_at_cost() {
+    for policy in policies(1.5) {
+        for g in [0, 7, 130] {
+            assert_eq!(policy.priority(g, 0, 3), g);
+        }
+    }
+}
+
+#[test]
+fn fractional_weights_on_integer_costs() {
+    // 10 + 1.5 * 7 rounds down instead of searching with w = 1
+    assert_eq!(StaticWeight::<i32>::new(1.5).priority(10, 7, 0), 20);
+    // halfway through the expected depth the weight is 1.5
+    assert_eq!(DynamicWeight::<i32>::new(1.0, 10).priority(0, 10, 5), 15);
+    assert_eq!(DynamicWeight::<i32>::new(1.0, 10).priority(0, 10, 10), 10);
+    assert_eq!(PwXd::<i32>::new(1.5).priority(10, 20, 0), 45);
+}
+
+#[test]
+fn large_priorities_saturate() {
+    for policy in policies(5.0) {
+        assert_eq!(policy.priority(i32::MAX / 2, i32::MAX / 2, 0), i32::MAX);
+    }
+}
+
+#[test]
+#[should_panic(expected = "below 1")]
+fn weights_below_one_panic() {
+    Xdp::<i32>::new(0.5);
+}
+
+#[test]
+#[should_panic(expected = "negative")]
+fn negative_epsilon_panics() {
+    DynamicWeight::<i32>::new(-0.5, 10);
+}
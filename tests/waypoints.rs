@@ -0,0 +1,68 @@
+mod common;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::waypoints::Router;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+fn router() -> Router<AStar<i32, Pos>> {
+    Router::new(AStar::with_refpool_size(10000))
+}
+
+#[test]
+fn nearest_is_the_cheapest_goal() {
+    let grid = Grid::new();
+    let goals = [(19, 19), (14, 17), (13, 2), (4, 10)];
+    for start in [(0, 0), (19, 0), (12, 19)] {
+        let (index, cost, path) = router().nearest(start, &goals.map(Octile), &grid).unwrap();
+        let cheapest = goals.iter().filter_map(|goal| astar_cost(&grid, start, *goal)).min();
+        assert_eq!(Some(cost), cheapest);
+        assert_eq!(astar_cost(&grid, start, goals[index]), Some(cost));
+        assert_eq!((path[0], *path.last().unwrap()), (start, goals[index]));
+        assert_eq!(path_cost(&grid, &path), cost);
+    }
+}
+
+#[test]
+fn route_visits_every_waypoint() {
+    let grid = Grid::new();
+    let (start, end) = ((0, 0), (19, 19));
+    let waypoints = [(13, 2), (4, 18), (18, 10), (8, 8)];
+    let route = router().route(start, &waypoints, Some(end), |pos| Octile(*pos), &grid).unwrap();
+
+    let mut order = route.order.clone();
+    order.sort_unstable();
+    assert_eq!(order, [0, 1, 2, 3]);
+    assert_eq!((route.path[0], *route.path.last().unwrap()), (start, end));
+    assert_eq!(path_cost(&grid, &route.path), route.cost);
+    // waypoints show up along the path in the chosen order
+    let mut rest = route.path.iter();
+    for k in &route.order {
+        assert!(rest.any(|pos| *pos == waypoints[*k]), "{:?} missing", waypoints[*k]);
+    }
+
+    // the cost is the sum of the optimal legs in the chosen order
+    let leg = |a: Pos, b: Pos| astar_cost(&grid, a, b).unwrap();
+    let mut stops = vec![start];
+    stops.extend(route.order.iter().map(|k| waypoints[*k]));
+    stops.push(end);
+    assert_eq!(route.cost, stops.windows(2).map(|w| leg(w[0], w[1])).sum::<i32>());
+
+    // on this grid nearest neighbor and 2-opt find the best order
+    let mut best = i32::MAX;
+    for a in 0..4 {
+        for b in (0..4).filter(|b| *b != a) {
+            for c in (0..4).filter(|c| ![a, b].contains(c)) {
+                let d = 6 - a - b - c;
+                let stops = [start, waypoints[a], waypoints[b], waypoints[c], waypoints[d], end];
+                best = best.min(stops.windows(2).map(|w| leg(w[0], w[1])).sum());
+            }
+        }
+    }
+    assert_eq!(route.cost, best);
+}
+
+#[test]
+fn unreachable_waypoint_fails() {
+    let grid = Grid::new();
+    assert!(router().route((0, 0), &[(13, 2), (14, 17)], None, |pos| Octile(*pos), &grid).is_none());
+}
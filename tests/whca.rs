@@ -0,0 +1,72 @@
+mod common;
+
+use std::collections::HashSet;
+
+use astar::pathfinding::whca::{TrueDistance, Whca};
+use astar::pathfinding::Goal;
+use common::{astar_cost, path_cost, Grid, Octile, Pos};
+
+fn at(path: &[(Pos, u32)], t: usize) -> Pos {
+    path.get(t).unwrap_or(path.last().unwrap()).0
+}
+
+#[test]
+fn true_distance_matches_astar() {
+    let grid = Grid::new();
+    let goal = (18, 2);
+    let distance = TrueDistance::new(goal, Octile((0, 0)), &grid, usize::MAX);
+    for pos in grid.free() {
+        assert_eq!(distance.distance(&pos), astar_cost(&grid, pos, goal), "{pos:?}");
+        assert_eq!(distance.heuristic(&pos), astar_cost(&grid, pos, goal).unwrap_or_default());
+    }
+    assert_eq!(distance.distance(&(14, 17)), None);
+}
+
+#[test]
+fn single_agent_matches_astar() {
+    let grid = Grid::new();
+    for (start, goal) in common::queries().into_iter().filter(|(_, goal)| *goal != (14, 17)) {
+        let paths = Whca::new(common::STRAIGHT, 8, 4, 10000).compute(&[(start, goal)], &grid, |pos| Octile(*pos), 200);
+        let path: Vec<_> = paths.unwrap()[0].iter().map(|(pos, _)| *pos).collect();
+        assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+        assert_eq!(Some(path_cost(&grid, &path)), astar_cost(&grid, start, goal));
+    }
+}
+
+#[test]
+fn agents_reach_goals_without_colliding() {
+    let grid = Grid::new();
+    let agents = [((0, 0), (4, 4)), ((4, 0), (0, 4)), ((0, 2), (4, 2)), ((2, 4), (2, 0))];
+    let paths = Whca::new(common::STRAIGHT, 8, 4, 10000).compute(&agents, &grid, |pos| Octile(*pos), 200).unwrap();
+
+    for ((start, goal), path) in agents.iter().zip(&paths) {
+        assert_eq!((path[0].0, path.last().unwrap().0), (*start, *goal));
+        assert!(path.windows(2).all(|w| w[1].1 == w[0].1 + 1));
+    }
+    let end = paths.iter().map(|path| path.len()).max().unwrap();
+    for t in 0..end {
+        for a in 0..paths.len() {
+            for b in a + 1..paths.len() {
+                let (pa, pb) = (at(&paths[a], t), at(&paths[b], t));
+                assert_ne!(pa, pb, "agents {a} and {b} meet at {t}");
+                let (na, nb) = (at(&paths[a], t + 1), at(&paths[b], t + 1));
+                assert!(!(pa == nb && pb == na), "agents {a} and {b} swap at {t}");
+            }
+        }
+    }
+}
+
+#[test]
+fn waiting_into_another_agent_fails() {
+    // a corridor, the second agent waits at its goal in the way of the first
+    let corridor = Grid {
+        width: 5,
+        height: 1,
+        walls: HashSet::new(),
+    };
+    let agents = [((0, 0), (4, 0)), ((2, 0), (2, 0))];
+    assert!(Whca::new(common::STRAIGHT, 8, 4, 10000).compute(&agents, &corridor, |pos| Octile(*pos), 200).is_none());
+    // the other way around the first one waits and the second can't pass
+    let agents = [((2, 0), (2, 0)), ((0, 0), (4, 0))];
+    assert!(Whca::new(common::STRAIGHT, 8, 4, 10000).compute(&agents, &corridor, |pos| Octile(*pos), 200).is_none());
+}
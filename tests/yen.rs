@@ -0,0 +1,61 @@
+mod common;
+
+use std::collections::HashSet;
+
+use astar::pathfinding::astar::AStar;
+use astar::pathfinding::yen::KShortest;
+use astar::pathfinding::Movements;
+use common::{astar_cost, path_cost, queries, Grid, Octile, Pos};
+
+/// Costs of every loopless path from start to goal, cheapest first
+fn all_costs(grid: &Grid, start: Pos, goal: Pos) -> Vec<i32> {
+    fn walk(grid: &Grid, pos: Pos, goal: Pos, cost: i32, seen: &mut HashSet<Pos>, costs: &mut Vec<i32>) {
+        if pos == goal {
+            costs.push(cost);
+            return;
+        }
+        for (next, step) in grid.get_neighbors(pos) {
+            if seen.insert(next) {
+                walk(grid, next, goal, cost + step, seen, costs);
+                seen.remove(&next);
+            }
+        }
+    }
+    let mut costs = vec![];
+    walk(grid, start, goal, 0, &mut HashSet::from([start]), &mut costs);
+    costs.sort_unstable();
+    costs
+}
+
+#[test]
+fn k_cheapest_match_enumeration() {
+    let grid = Grid {
+        width: 4,
+        height: 3,
+        walls: HashSet::from([(1, 1)]),
+    };
+    let mut yen = KShortest::new(AStar::<i32, Pos>::with_refpool_size(1000));
+    let (start, goal) = ((0, 0), (3, 2));
+    let paths = yen.compute(start, Octile(goal), &grid, 12);
+    let costs: Vec<i32> = paths.iter().map(|(cost, _)| *cost).collect();
+    assert_eq!(costs, all_costs(&grid, start, goal)[..12]);
+
+    let mut distinct = HashSet::new();
+    for (cost, path) in &paths {
+        assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+        assert_eq!(path_cost(&grid, path), *cost);
+        assert_eq!(path.iter().collect::<HashSet<_>>().len(), path.len(), "loop in {path:?}");
+        assert!(distinct.insert(path.clone()));
+    }
+}
+
+#[test]
+fn first_path_is_the_cheapest() {
+    let grid = Grid::new();
+    let mut yen = KShortest::new(AStar::<i32, Pos>::with_refpool_size(10000));
+    for (start, goal) in queries() {
+        let paths = yen.compute(start, Octile(goal), &grid, 3);
+        assert_eq!(paths.first().map(|(cost, _)| *cost), astar_cost(&grid, start, goal));
+        assert!(paths.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+}